@@ -0,0 +1,184 @@
+//
+// Timing of the stars, over repeated runs
+//
+// Each run reads the input again, parses it when the day has a parse step, then solves
+// the star. Stars parse their input themselves, so the solve time of a run is the time
+// of the star less the parse time of the same run; for the days without a parse step,
+// parsing is part of the solve time. Results are printed as a table or as CSV, and the CSV of a previous
+// run can be used as a baseline: a star is slower when its median solve time exceeds
+// the one of the baseline by more than a tolerance
+//
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::time::{ Duration, Instant };
+use crate::{ Day, Solution };
+use crate::input::{ InputError, Source };
+
+pub const CSV_HEADER: &str = "day,star,runs,read_us,parse_us,min_us,median_us,max_us,total_us";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Timing {
+    pub day: usize,
+    pub star: usize,
+    // median time to read the input over the runs
+    pub read: Duration,
+    // median parse time, None without a parse step
+    pub parse: Option<Duration>,
+    // solve times, sorted
+    pub runs: Vec<Duration>
+}
+
+// Median solve times of a previous run, by day and star
+pub type Baseline = BTreeMap<(usize, usize), Duration>;
+
+impl Timing {
+    pub fn min(&self) -> Duration {
+        self.runs[0]
+    }
+
+    pub fn median(&self) -> Duration {
+        self.runs[self.runs.len() / 2]
+    }
+
+    pub fn max(&self) -> Duration {
+        self.runs[self.runs.len() - 1]
+    }
+
+    pub fn total(&self) -> Duration {
+        self.runs.iter().sum()
+    }
+
+    // Relative slowdown of the median against the baseline, when above the tolerance
+    pub fn slowdown(&self, baseline: &Baseline, tolerance: f64) -> Option<f64> {
+        let reference = baseline.get(&(self.day, self.star))?.as_secs_f64();
+        let ratio = self.median().as_secs_f64() / reference.max(f64::MIN_POSITIVE) - 1.0;
+        if ratio > tolerance { Some(ratio) } else { None }
+    }
+}
+
+pub fn measure(day: &Day, star: usize, source: &Source, runs: usize) -> Result<Timing, InputError> {
+    let mut reads = Vec::new();
+    let mut parses = Vec::new();
+    let mut solves = Vec::new();
+    for _ in 0..runs.max(1) {
+        let start = Instant::now();
+        let contents = source.read(day)?;
+        reads.push(start.elapsed());
+        let parse = day.parse.map_or(Duration::ZERO, |parse| {
+            let start = Instant::now();
+            parse(&contents);
+            start.elapsed()
+        });
+        parses.push(parse);
+        let start = Instant::now();
+        let answer = day.star(star, &contents);
+        solves.push(start.elapsed().saturating_sub(parse));
+        drop(answer);
+    }
+    reads.sort();
+    parses.sort();
+    solves.sort();
+    let parse = day.parse.map(|_| parses[parses.len() / 2]);
+    Ok(Timing { day: day.day, star, read: reads[reads.len() / 2], parse, runs: solves })
+}
+
+pub fn table(timings: &[Timing], baseline: Option<&Baseline>, tolerance: f64) -> String {
+    let mut table = format!("{:>3} {:>4} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10}\n", "day", "star", "read", "parse", "min", "median", "max", "total");
+    for timing in timings {
+        write!(table, "{:>3} {:>4} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10}",
+            timing.day, timing.star, duration(timing.read), timing.parse.map_or("-".to_string(), duration), duration(timing.min()),
+            duration(timing.median()), duration(timing.max()), duration(timing.total())).unwrap();
+        if let Some(slowdown) = baseline.and_then(|baseline| timing.slowdown(baseline, tolerance)) {
+            write!(table, "  SLOWER +{:.0}%", slowdown * 100.0).unwrap();
+        }
+        table.push('\n');
+    }
+    let total = timings.iter().map(|t| t.total() + (t.read + t.parse.unwrap_or_default()) * t.runs.len() as u32).sum::<Duration>();
+    writeln!(table, "total elapsed {}", duration(total)).unwrap();
+    table
+}
+
+pub fn csv(timings: &[Timing]) -> String {
+    let micros = |d: Duration| d.as_secs_f64() * 1e6;
+    let mut csv = format!("{}\n", CSV_HEADER);
+    for t in timings {
+        let parse = t.parse.map_or(String::new(), |parse| format!("{:.1}", micros(parse)));
+        writeln!(csv, "{},{},{},{:.1},{},{:.1},{:.1},{:.1},{:.1}", t.day, t.star, t.runs.len(),
+            micros(t.read), parse, micros(t.min()), micros(t.median()), micros(t.max()), micros(t.total())).unwrap();
+    }
+    csv
+}
+
+// Reads the medians of a CSV written by `csv`
+pub fn parse_baseline(csv: &str) -> Result<Baseline, String> {
+    let mut lines = csv.lines().enumerate();
+    match lines.next() {
+        Some((_, header)) if header.trim() == CSV_HEADER => (),
+        _ => return Err(format!("line 1: expected the header {}", CSV_HEADER))
+    }
+    lines
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            let fields = line.split(',').map(|f| f.trim()).collect::<Vec<_>>();
+            let day = fields[0].parse::<usize>();
+            let star = fields.get(1).map(|f| f.parse::<usize>());
+            let median = fields.get(6).map(|f| f.parse::<f64>());
+            match (day, star, median) {
+                (Ok(day), Some(Ok(star)), Some(Ok(median))) if median >= 0.0 =>
+                    Ok(((day, star), Duration::from_secs_f64(median / 1e6))),
+                _ => Err(format!("line {}: wrong timing {}", i + 1, line))
+            }
+        })
+        .collect()
+}
+
+pub fn duration(duration: Duration) -> String {
+    let seconds = duration.as_secs_f64();
+    if seconds >= 1.0 {
+        format!("{:.2}s", seconds)
+    } else if seconds >= 1e-3 {
+        format!("{:.2}ms", seconds * 1e3)
+    } else {
+        format!("{:.1}µs", seconds * 1e6)
+    }
+}
+
+#[cfg(test)]
+fn timing(day: usize, star: usize, parse: Option<u64>, runs: &[u64]) -> Timing {
+    Timing { day, star, read: Duration::from_micros(5), parse: parse.map(Duration::from_micros), runs: runs.iter().map(|&us| Duration::from_micros(us)).collect() }
+}
+
+#[test]
+fn test0_bench() {
+    let timings = [timing(12, 2, Some(20), &[100, 200, 900]), timing(18, 1, None, &[1500, 2000, 2500])];
+    assert_eq!(timings[0].median(), Duration::from_micros(200));
+    assert_eq!(timings[1].total(), Duration::from_micros(6000));
+    assert_eq!(csv(&timings), format!("{}\n\
+        12,2,3,5.0,20.0,100.0,200.0,900.0,1200.0\n\
+        18,1,3,5.0,,1500.0,2000.0,2500.0,6000.0\n", CSV_HEADER));
+
+    let baseline = parse_baseline(&csv(&[timing(12, 2, Some(20), &[100]), timing(18, 1, None, &[1000])])).unwrap();
+    assert_eq!(timings[0].slowdown(&baseline, 1.5), None);
+    assert_eq!(timings[0].slowdown(&baseline, 0.5), Some(1.0));
+    assert_eq!(timings[1].slowdown(&baseline, 0.5), Some(1.0));
+    let table = table(&timings, Some(&baseline), 0.5);
+    assert!(table.lines().nth(2).unwrap().ends_with("2.50ms     6.00ms  SLOWER +100%"));
+    assert!(table.lines().nth(1).unwrap().starts_with(" 12    2      5.0µs     20.0µs    100.0µs"));
+    assert!(table.lines().nth(2).unwrap().starts_with(" 18    1      5.0µs          -     1.50ms"));
+    assert!(table.ends_with("total elapsed 7.29ms\n"));
+    assert_eq!(parse_baseline("day,star\n"), Err(format!("line 1: expected the header {}", CSV_HEADER)));
+    assert_eq!(parse_baseline(&format!("{}\n1,x", CSV_HEADER)), Err("line 2: wrong timing 1,x".to_string()));
+}
+
+#[test]
+fn test1_bench() {
+    let day = crate::day(1).unwrap();
+    let timing = measure(day, 2, &Source::Inline("14\n1969".to_string()), 3).unwrap();
+    assert_eq!((timing.day, timing.star, timing.runs.len()), (1, 2, 3));
+    assert!(timing.min() <= timing.median() && timing.median() <= timing.max());
+    assert_eq!(timing.parse, None);
+    let day = crate::day(6).unwrap();
+    assert!(measure(day, 1, &Source::Inline("COM)B\nB)C".to_string()), 1).unwrap().parse.is_some());
+    assert_eq!(duration(Duration::from_millis(1500)), "1.50s");
+    assert_eq!(duration(Duration::from_nanos(2500)), "2.5µs");
+}
@@ -0,0 +1,367 @@
+//
+// Command line of the runner
+//
+// A subcommand, `run` when omitted, followed by its arguments and options, which can be
+// given in any order, as `--name value` or `--name=value`. Days and stars are lists of
+// numbers and ranges, like `3-7,12`. Errors are usage errors, reported with exit code 2
+//
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::Write;
+use adventofcode2019::{ Day, YEAR };
+use adventofcode2019::input::Source;
+use adventofcode2019::pool;
+
+const NAME: &str = env!("CARGO_PKG_NAME");
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Run { days: Vec<&'static Day>, stars: Vec<usize>, source: Source, json: bool, workers: usize, memory: bool },
+    Verify { answers: String, days: Vec<&'static Day>, source: Source },
+    Bench { days: Vec<&'static Day>, stars: Vec<usize>, source: Source, runs: usize, csv: bool, save: Option<String>, baseline: Option<String>, tolerance: f64 },
+    Watch { days: Vec<&'static Day> },
+    List { year: usize },
+    New { year: usize, day: usize },
+    Help(String),
+    Disasm { program: String },
+    Asm { source: String, output: Option<String> },
+    Compile { program: String, output: Option<String> },
+    Cfg { program: String, graph: Option<String> },
+    Decompile { program: String, output: Option<String> },
+    Debug { program: String },
+    Exec { program: String, inputs: String, outputs: String },
+    Trace { program: String, trace: String, inputs: Option<String> },
+    // the session stops before the input at index inputs when given
+    Record { day: usize, session: Option<String>, inputs: Option<usize> },
+    Replay { program: String, session: String },
+    Profile { program: String, inputs: Option<String> }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CliError(pub String);
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}\nrun `{} help` for usage", self.0, NAME)
+    }
+}
+
+struct Spec {
+    name: &'static str,
+    args: &'static str,
+    about: &'static str,
+    // number of arguments, min and max
+    count: (usize, usize),
+    options: &'static [&'static str]
+}
+
+// name, value, help
+const OPTIONS: &[(&str, Option<&str>, &str)] = &[
+    ("--input", Some("PATH"), "input file, or directory of dayXX.txt files, ./input by default"),
+    ("--stdin", None, "read the input of the day from the standard input"),
+    ("--text", Some("INPUT"), "input of the day given on the command line"),
+    ("--format", Some("FORMAT"), "text, by default, or json for one object per line"),
+    ("--jobs", Some("N"), "solve stars on N worker threads, auto for one per core"),
+    ("--memory", None, "report the heap usage of each star, solving one star at a time"),
+    ("--runs", Some("N"), "number of runs of each star, 1 by default"),
+    ("--csv", None, "print the timings as CSV instead of a table"),
+    ("--save", Some("FILE"), "save the timings as CSV"),
+    ("--baseline", Some("FILE"), "flag the stars slower than in a saved CSV"),
+    ("--tolerance", Some("PERCENT"), "slowdown allowed against the baseline, 10 by default"),
+    ("--year", Some("YEAR"), "event of the days, 2019 by default"),
+    ("--help", None, "print the help of the command")
+];
+
+const INPUTS: [&str; 3] = ["--input", "--stdin", "--text"];
+
+const COMMANDS: &[Spec] = &[
+    Spec { name: "run", args: "[DAYS [STARS]]", about: "Solve stars, all of them by default", count: (0, 2),
+        options: &["--input", "--stdin", "--text", "--format", "--jobs", "--memory", "--year", "--help"] },
+    Spec { name: "verify", args: "ANSWERS [DAYS]", about: "Check the answers against a TOML or JSON file of expected answers", count: (1, 2),
+        options: &["--input", "--year", "--help"] },
+    Spec { name: "bench", args: "[DAYS [STARS]]", about: "Time stars", count: (0, 2),
+        options: &["--input", "--stdin", "--text", "--runs", "--csv", "--save", "--baseline", "--tolerance", "--year", "--help"] },
+    Spec { name: "watch", args: "[DAYS]", about: "Solve the stars and run the tests of a day again whenever its module or its input change", count: (0, 1),
+        options: &["--year", "--help"] },
+    Spec { name: "list", args: "", about: "List the days and what their stars are about", count: (0, 0), options: &["--year", "--help"] },
+    Spec { name: "new", args: "DAY", about: "Create the module of a day from a template and register it, with its year", count: (1, 1),
+        options: &["--year", "--help"] },
+    Spec { name: "help", args: "[COMMAND]", about: "Print the help of a command", count: (0, 1), options: &[] }
+];
+
+// Commands working on IntCode programs, listed apart in the help
+const TOOLS: &[Spec] = &[
+    Spec { name: "disasm", args: "PROGRAM", about: "Print the listing of an IntCode program", count: (1, 1), options: &["--help"] },
+    Spec { name: "asm", args: "SOURCE [PROGRAM]", about: "Assemble a listing into an IntCode program", count: (1, 2), options: &["--help"] },
+    Spec { name: "compile", args: "PROGRAM [MODULE]", about: "Translate an IntCode program to a Rust module", count: (1, 2), options: &["--help"] },
+    Spec { name: "cfg", args: "PROGRAM [GRAPH]", about: "Print the functions, data and self-modifying writes of an IntCode program, saving its control-flow graph as Graphviz DOT", count: (1, 2), options: &["--help"] },
+    Spec { name: "decompile", args: "PROGRAM [PSEUDOCODE]", about: "Decompile an IntCode program to structured pseudo-code", count: (1, 2), options: &["--help"] },
+    Spec { name: "debug", args: "PROGRAM", about: "Debug an IntCode program interactively", count: (1, 1), options: &["--help"] },
+    Spec { name: "exec", args: "PROGRAM INPUTS OUTPUTS", about: "Run an IntCode program with the values of a file, writing its outputs to another one", count: (3, 3), options: &["--help"] },
+    Spec { name: "trace", args: "PROGRAM TRACE [INPUTS]", about: "Run an IntCode program, saving the trace of its instructions and printing its profile", count: (2, 3), options: &["--help"] },
+    Spec { name: "record", args: "DAY [SESSION [INPUTS]]", about: "Save the inputs and outputs of the IntCode program of an interactive day (13, 15, 25) as its solution plays it, up to a number of inputs", count: (1, 3), options: &["--help"] },
+    Spec { name: "replay", args: "PROGRAM SESSION", about: "Run an IntCode program with the inputs of a session, checking it gives the same outputs", count: (2, 2), options: &["--help"] },
+    Spec { name: "profile", args: "PROGRAM [INPUTS]", about: "Run an IntCode program and print where it spends its cycles", count: (1, 2), options: &["--help"] }
+];
+
+fn spec(name: &str) -> Option<&'static Spec> {
+    COMMANDS.iter().chain(TOOLS).find(|spec| spec.name == name)
+}
+
+// Arguments without the name of the program
+pub fn parse(args: &[String]) -> Result<Command, CliError> {
+    let error = |message: String| CliError(message);
+    let (spec, args) = match args.first() {
+        Some(name) if !name.starts_with("--") && !name.starts_with(|c: char| c.is_ascii_digit()) => {
+            let spec = spec(name).ok_or_else(|| error(format!("unknown command {}", name)))?;
+            (spec, &args[1..])
+        },
+        _ => (&COMMANDS[0], args)
+    };
+
+    let mut positionals = Vec::new();
+    let mut options: HashMap<&str, Option<String>> = HashMap::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if !arg.starts_with("--") {
+            positionals.push(arg.clone());
+            continue;
+        }
+        let (name, value) = match arg.split_once('=') {
+            Some((name, value)) => (name, Some(value.to_string())),
+            None => (arg.as_str(), None)
+        };
+        let &(name, takes, _) = OPTIONS
+            .iter()
+            .find(|option| option.0 == name && spec.options.contains(&name))
+            .ok_or_else(|| error(format!("unknown option {} for {}", name, spec.name)))?;
+        let value = match (takes, value) {
+            (Some(_), Some(value)) => Some(value),
+            (Some(_), None) => Some(args.next().ok_or_else(|| error(format!("{} needs a value", name)))?.clone()),
+            (None, Some(_)) => return Err(error(format!("{} takes no value", name))),
+            (None, None) => None
+        };
+        if options.insert(name, value).is_some() {
+            return Err(error(format!("{} is given twice", name)));
+        }
+    }
+    if options.contains_key("--help") {
+        return Ok(Command::Help(help(Some(spec.name))?));
+    }
+    if positionals.len() < spec.count.0 || positionals.len() > spec.count.1 {
+        return Err(error(format!("usage: {} {} {}", NAME, spec.name, spec.args).trim_end().to_string()));
+    }
+
+    let value = |name: &str| options.get(name).cloned().flatten();
+    let number = |name: &str, default: usize| match value(name) {
+        Some(n) => n.parse::<usize>().ok().filter(|&n| n > 0).ok_or_else(|| error(format!("{} needs a positive number, not {}", name, n))),
+        None => Ok(default)
+    };
+    let positional = |i: usize| positionals.get(i).cloned();
+    let year = match value("--year") {
+        Some(year) => year.parse::<usize>().map_err(|_| error(format!("--year needs a year, not {}", year)))?,
+        None => YEAR
+    };
+    let days = |i: usize| {
+        let registered = adventofcode2019::year(year).map_or(&[][..], |year| year.days);
+        let mut days = match positionals.get(i) {
+            Some(days) => numbers(days, 25)
+                .map_err(|e| error(format!("days {}", e)))?
+                .into_iter()
+                .map(|day| registered.iter().find(|d| d.day == day).ok_or_else(|| error(format!("day {} of {} is not solved", day, year))))
+                .collect::<Result<Vec<_>, _>>()?,
+            None => registered.iter().collect()
+        };
+        days.sort_by_key(|day| day.day);
+        Ok(days)
+    };
+    let stars = |i: usize| match positionals.get(i) {
+        Some(stars) => numbers(stars, 2).map_err(|e| error(format!("stars {}", e))),
+        None => Ok(vec![1, 2])
+    };
+    let source = |days: &[&Day]| {
+        let mut sources = INPUTS.iter().filter(|name| options.contains_key(*name));
+        let source = match sources.next() {
+            Some(&"--input") => Source::Path(value("--input").unwrap().into()),
+            Some(&"--stdin") => Source::Stdin,
+            Some(_) => Source::Inline(value("--text").unwrap()),
+            None => Source::Default
+        };
+        if sources.next().is_some() {
+            Err(error("only one of --input, --stdin and --text can be given".to_string()))
+        } else if source.is_single() && days.len() > 1 {
+            Err(error("a single input needs a single day".to_string()))
+        } else {
+            Ok(source)
+        }
+    };
+
+    Ok(match spec.name {
+        "run" => {
+            let days = days(0)?;
+            let json = match value("--format").as_deref() {
+                None | Some("text") => false,
+                Some("json") => true,
+                Some(format) => return Err(error(format!("unknown format {}, expected text or json", format)))
+            };
+            let workers = match value("--jobs").as_deref() {
+                Some("auto") => pool::default_workers(),
+                _ => number("--jobs", 1)?
+            };
+            let memory = options.contains_key("--memory");
+            if memory && workers > 1 {
+                return Err(error("--memory solves one star at a time, it can't be given with --jobs".to_string()));
+            }
+            Command::Run { source: source(&days)?, days, stars: stars(1)?, json, workers, memory }
+        },
+        "verify" => {
+            let days = days(1)?;
+            Command::Verify { answers: positional(0).unwrap(), source: source(&days)?, days }
+        },
+        "bench" => {
+            let days = days(0)?;
+            let tolerance = match value("--tolerance") {
+                Some(percent) => percent.parse::<f64>().ok().filter(|p| p.is_finite() && *p >= 0.0)
+                    .ok_or_else(|| error(format!("--tolerance needs a percentage of at least 0, not {}", percent)))? / 100.0,
+                None => 0.1
+            };
+            Command::Bench {
+                source: source(&days)?, days, stars: stars(1)?, runs: number("--runs", 1)?, csv: options.contains_key("--csv"),
+                save: value("--save"), baseline: value("--baseline"), tolerance
+            }
+        },
+        "watch" => Command::Watch { days: days(0)? },
+        "list" => Command::List { year },
+        "new" => Command::New { year, day: numbers(&positional(0).unwrap(), 25).ok().filter(|days| days.len() == 1)
+            .ok_or_else(|| error(format!("day must be a number between 1 and 25, not {}", positional(0).unwrap())))?[0] },
+        "help" => Command::Help(help(positional(0).as_deref())?),
+        "disasm" => Command::Disasm { program: positional(0).unwrap() },
+        "asm" => Command::Asm { source: positional(0).unwrap(), output: positional(1) },
+        "compile" => Command::Compile { program: positional(0).unwrap(), output: positional(1) },
+        "cfg" => Command::Cfg { program: positional(0).unwrap(), graph: positional(1) },
+        "decompile" => Command::Decompile { program: positional(0).unwrap(), output: positional(1) },
+        "debug" => Command::Debug { program: positional(0).unwrap() },
+        "exec" => Command::Exec { program: positional(0).unwrap(), inputs: positional(1).unwrap(), outputs: positional(2).unwrap() },
+        "trace" => Command::Trace { program: positional(0).unwrap(), trace: positional(1).unwrap(), inputs: positional(2) },
+        "record" => Command::Record { day: numbers(&positional(0).unwrap(), 25).ok().filter(|days| days.len() == 1)
+            .ok_or_else(|| error(format!("day must be a number between 1 and 25, not {}", positional(0).unwrap())))?[0], session: positional(1),
+            inputs: positional(2).map(|n| n.parse::<usize>().map_err(|_| error(format!("inputs must be a number, not {}", n)))).transpose()? },
+        "replay" => Command::Replay { program: positional(0).unwrap(), session: positional(1).unwrap() },
+        _ => Command::Profile { program: positional(0).unwrap(), inputs: positional(1) }
+    })
+}
+
+// Sorted numbers between 1 and max, from a list of numbers and ranges
+pub fn numbers(list: &str, max: usize) -> Result<Vec<usize>, String> {
+    let number = |n: &str| n.trim()
+        .parse::<usize>()
+        .ok()
+        .filter(|n| (1..=max).contains(n))
+        .ok_or(format!("must be numbers between 1 and {}, not {}", max, n.trim()));
+    let mut numbers = Vec::new();
+    for item in list.split(',') {
+        match item.split_once('-') {
+            Some((first, last)) => {
+                let (first, last) = (number(first)?, number(last)?);
+                if first > last {
+                    return Err(format!("range {} is empty", item));
+                }
+                numbers.extend(first..=last);
+            },
+            None => numbers.push(number(item)?)
+        }
+    }
+    numbers.sort_unstable();
+    numbers.dedup();
+    Ok(numbers)
+}
+
+// Help of the command, or of the whole runner
+pub fn help(command: Option<&str>) -> Result<String, CliError> {
+    let mut help = String::new();
+    match command {
+        None => {
+            writeln!(help, "Advent of Code 2019\n").unwrap();
+            writeln!(help, "Usage: {} [COMMAND] [ARGS] [OPTIONS]\n", NAME).unwrap();
+            writeln!(help, "Commands:").unwrap();
+            for spec in COMMANDS {
+                writeln!(help, "  {:<9} {}", spec.name, spec.about).unwrap();
+            }
+            writeln!(help, "IntCode tools:").unwrap();
+            for spec in TOOLS {
+                writeln!(help, "  {:<9} {}", spec.name, spec.about).unwrap();
+            }
+            writeln!(help, "\nDAYS and STARS are lists of numbers and ranges, like 3-7,12").unwrap();
+            writeln!(help, "Run `{} help COMMAND` for the arguments and options of a command", NAME).unwrap();
+        },
+        Some(name) => {
+            let spec = spec(name).ok_or_else(|| CliError(format!("unknown command {}", name)))?;
+            writeln!(help, "{}\n", spec.about).unwrap();
+            writeln!(help, "Usage: {} {} {}", NAME, spec.name, spec.args).unwrap();
+            if !spec.options.is_empty() {
+                writeln!(help, "\nOptions:").unwrap();
+            }
+            for (name, value, about) in OPTIONS.iter().filter(|option| spec.options.contains(&option.0)) {
+                let option = format!("{} {}", name, value.unwrap_or(""));
+                writeln!(help, "  {:<20} {}", option.trim_end(), about).unwrap();
+            }
+        }
+    }
+    Ok(help)
+}
+
+#[cfg(test)]
+fn args(line: &str) -> Vec<String> {
+    line.split_whitespace().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn test0_cli() {
+    assert_eq!(numbers("3-7,12", 25), Ok(vec![3, 4, 5, 6, 7, 12]));
+    assert_eq!(numbers("2,1,2", 2), Ok(vec![1, 2]));
+    assert_eq!(numbers("0", 25), Err("must be numbers between 1 and 25, not 0".to_string()));
+    assert_eq!(numbers("7-3", 25), Err("range 7-3 is empty".to_string()));
+    assert_eq!(numbers("1,x", 25), Err("must be numbers between 1 and 25, not x".to_string()));
+}
+
+#[test]
+fn test1_cli() {
+    let days = |days: &[usize]| days.iter().map(|&day| adventofcode2019::day(day).unwrap()).collect::<Vec<_>>();
+    assert_eq!(parse(&args("")), Ok(Command::Run { days: days(&(1..=25).collect::<Vec<_>>()), stars: vec![1, 2], source: Source::Default, json: false, workers: 1, memory: false }));
+    assert_eq!(parse(&args("3-4 2 --format=json --jobs 2")), Ok(Command::Run { days: days(&[3, 4]), stars: vec![2], source: Source::Default, json: true, workers: 2, memory: false }));
+    assert!(matches!(parse(&args("16 --memory")), Ok(Command::Run { workers: 1, memory: true, .. })));
+    assert_eq!(parse(&args("run 4 --text 1-2")), Ok(Command::Run { days: days(&[4]), stars: vec![1, 2], source: Source::Inline("1-2".to_string()), json: false, workers: 1, memory: false }));
+    assert_eq!(parse(&args("verify answers.toml 1,2 --year 2019")), Ok(Command::Verify { answers: "answers.toml".to_string(), days: days(&[1, 2]), source: Source::Default }));
+    assert_eq!(parse(&args("watch 16,18")), Ok(Command::Watch { days: days(&[16, 18]) }));
+    assert_eq!(parse(&args("new 5 --year 2020")), Ok(Command::New { year: 2020, day: 5 }));
+    assert!(matches!(parse(&args("bench 12 --runs 5 --tolerance 50")), Ok(Command::Bench { runs: 5, tolerance, .. }) if tolerance == 0.5));
+    assert_eq!(parse(&args("cfg a.txt a.dot")), Ok(Command::Cfg { program: "a.txt".to_string(), graph: Some("a.dot".to_string()) }));
+    assert_eq!(parse(&args("decompile a.txt")), Ok(Command::Decompile { program: "a.txt".to_string(), output: None }));
+    assert_eq!(parse(&args("record 13")), Ok(Command::Record { day: 13, session: None, inputs: None }));
+    assert_eq!(parse(&args("record 15 a.session 10")), Ok(Command::Record { day: 15, session: Some("a.session".to_string()), inputs: Some(10) }));
+    assert_eq!(parse(&args("replay a.txt a.session")), Ok(Command::Replay { program: "a.txt".to_string(), session: "a.session".to_string() }));
+    assert_eq!(parse(&args("exec a b c")), Ok(Command::Exec { program: "a".to_string(), inputs: "b".to_string(), outputs: "c".to_string() }));
+    assert!(matches!(parse(&args("bench --help")), Ok(Command::Help(help)) if help.contains("--baseline FILE")));
+    assert!(matches!(parse(&args("disasm --help")), Ok(Command::Help(help)) if help.contains("disasm PROGRAM")));
+    assert!(help(None).unwrap().contains("Print the help of a command\nIntCode tools:\n  disasm "));
+}
+
+#[test]
+fn test2_cli() {
+    let error = |line: &str| parse(&args(line)).unwrap_err().0;
+    assert_eq!(error("26"), "days must be numbers between 1 and 25, not 26");
+    assert_eq!(error("1 --year 2018"), "day 1 of 2018 is not solved");
+    assert_eq!(error("new 1-2"), "day must be a number between 1 and 25, not 1-2");
+    assert_eq!(error("1 3"), "stars must be numbers between 1 and 2, not 3");
+    assert_eq!(error("fly"), "unknown command fly");
+    assert_eq!(error("list --csv"), "unknown option --csv for list");
+    assert_eq!(error("1 --stdin --text 5"), "only one of --input, --stdin and --text can be given");
+    assert_eq!(error("1-2 --stdin"), "a single input needs a single day");
+    assert_eq!(error("bench --runs 0"), "--runs needs a positive number, not 0");
+    for tolerance in ["-5", "NaN", "inf", "ten"] {
+        assert_eq!(error(&format!("bench --tolerance {}", tolerance)), format!("--tolerance needs a percentage of at least 0, not {}", tolerance));
+    }
+    assert_eq!(error("run --jobs"), "--jobs needs a value");
+    assert_eq!(error("--memory --jobs 4"), "--memory solves one star at a time, it can't be given with --jobs");
+    assert_eq!(error("verify"), format!("usage: {} verify ANSWERS [DAYS]", NAME));
+}
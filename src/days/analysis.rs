@@ -0,0 +1,517 @@
+//
+// Static analysis of IntCode programs: control-flow graph, functions and data flow
+//
+// Instructions are the ones the disassembler finds from address 0, split in basic blocks
+// at jump targets, after jumps and at the code addresses the program pushes on its stack.
+// Functions follow the convention of the puzzle programs:
+//   - a call pushes the address following its jump at @0, the return address
+//   - the function moves the relative base over its frame, `ARB #n`, on entry
+//   - it returns by moving the base back and jumping to the return address, `JZ #0, @-n`
+// Calls through pointers jump to a relative target after pushing the return address,
+// other code addresses pushed on the stack are taken as pointers to functions.
+// Data flow is limited to the addresses read and written in position mode, writes into
+// instructions are the program patching itself, usually the operands of indexed accesses
+//
+use std::collections::{ BTreeMap, BTreeSet };
+use std::convert::TryFrom;
+use std::fmt::Write;
+use crate::disassembler::{ decode_at, line_at };
+use crate::intcode::{ Instruction, Mode, Opcode };
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exit {
+    // falls through to the next block
+    Next(usize),
+    Jump(usize),
+    // conditional jump, the target is unknown when it's read from memory
+    Branch { target: Option<usize>, next: usize },
+    // call of the function at target, unknown for pointers, returning to the next block
+    Call { target: Option<usize>, ret: usize },
+    Return,
+    // jump to a target read from memory which is not a return
+    Indirect,
+    Halt,
+    // runs into words that are not instructions
+    End
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Block {
+    pub start: usize,
+    pub instructions: Vec<usize>,
+    pub exit: Exit,
+    // addresses read and written in position mode
+    pub reads: BTreeSet<usize>,
+    pub writes: BTreeSet<usize>
+}
+
+impl Block {
+    // Blocks run after this one in the same function
+    pub fn successors(&self) -> Vec<usize> {
+        match self.exit {
+            Exit::Next(next) | Exit::Jump(next) | Exit::Call { ret: next, .. } => vec![next],
+            Exit::Branch { target, next } => target.into_iter().chain(Some(next)).collect(),
+            Exit::Return | Exit::Indirect | Exit::Halt | Exit::End => vec![]
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Function {
+    pub entry: usize,
+    // size of the frame reserved on entry
+    pub frame: Option<i64>,
+    pub blocks: BTreeSet<usize>,
+    pub calls: BTreeSet<usize>,
+    pub pointer_calls: usize
+}
+
+// Write of the instruction at pos into the instruction at target
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Patch {
+    pub pos: usize,
+    pub address: usize,
+    pub target: usize
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Graph {
+    pub blocks: BTreeMap<usize, Block>,
+    pub functions: Vec<Function>,
+    pub patches: Vec<Patch>
+}
+
+pub fn analyze(codes: &[i64]) -> Graph {
+    let starts = explore(codes);
+    let decode = |address: usize| Instruction::decode(codes[address]).unwrap();
+    let params = |address: usize| &codes[address + 1..address + decode(address).size()];
+
+    // Code addresses pushed on the stack that are not return addresses
+    let pointers = starts
+        .iter()
+        .filter_map(|&address| pushed(&decode(address), params(address)))
+        .filter(|&address| starts.contains(&address) && is_entry(codes, address))
+        .collect::<BTreeSet<_>>();
+    let mut leaders = pointers.clone();
+    leaders.insert(0);
+    for &address in &starts {
+        let instruction = decode(address);
+        if is_jump(&instruction) {
+            leaders.extend(jump_target(codes, &instruction, params(address)).filter(|target| starts.contains(target)));
+        }
+        if is_jump(&instruction) || instruction.opcode == Opcode::Hlt {
+            leaders.extend(Some(address + instruction.size()).filter(|next| starts.contains(next)));
+        }
+    }
+
+    let mut blocks = BTreeMap::new();
+    for &start in leaders.iter().filter(|leader| starts.contains(leader)) {
+        let mut block = Block { start, instructions: Vec::new(), exit: Exit::End, reads: BTreeSet::new(), writes: BTreeSet::new() };
+        let mut address = start;
+        loop {
+            let instruction = decode(address);
+            block.instructions.push(address);
+            accesses(&instruction, address, params(address), &mut block);
+            let next = address + instruction.size();
+            if instruction.opcode == Opcode::Hlt {
+                block.exit = Exit::Halt;
+                break;
+            }
+            if is_jump(&instruction) {
+                block.exit = exit(codes, &starts, address, next);
+                break;
+            }
+            if leaders.contains(&next) || !starts.contains(&next) {
+                block.exit = if starts.contains(&next) { Exit::Next(next) } else { Exit::End };
+                break;
+            }
+            address = next;
+        }
+        blocks.insert(start, block);
+    }
+
+    // Entry of the program, called functions and pointers to functions
+    let mut entries = pointers;
+    entries.insert(0);
+    entries.extend(blocks.values().filter_map(|block| match block.exit {
+        Exit::Call { target, .. } => target,
+        _ => None
+    }));
+    let functions = entries
+        .into_iter()
+        .filter(|entry| blocks.contains_key(entry))
+        .map(|entry| function(&blocks, codes, entry))
+        .collect();
+
+    let mut owners = BTreeMap::new();
+    for &address in &starts {
+        for word in address..address + decode(address).size() {
+            owners.insert(word, address);
+        }
+    }
+    let patches = starts
+        .iter()
+        .flat_map(|&pos| {
+            let targets = written(&decode(pos), pos, params(pos));
+            targets.into_iter().filter_map(|address| owners.get(&address).map(|&target| Patch { pos, address, target })).collect::<Vec<_>>()
+        })
+        .collect();
+
+    Graph { blocks, functions, patches }
+}
+
+// Addresses of the instructions run from address 0, more careful than the disassembler
+// with the constants pushed on the stack: the ones following the jumps they come before
+// are return addresses, other ones are pointers to functions when they start with
+// `ARB #n` and don't overlap code
+fn explore(codes: &[i64]) -> BTreeSet<usize> {
+    let mut starts = BTreeSet::new();
+    let mut constants = BTreeSet::new();
+    let mut pending = vec![0];
+    loop {
+        while let Some(address) = pending.pop() {
+            if starts.contains(&address) {
+                continue;
+            }
+            let instruction = match decode_at(codes, address) {
+                Some(instruction) => instruction,
+                None => continue
+            };
+            starts.insert(address);
+            let params = &codes[address + 1..address + instruction.size()];
+            let next = address + instruction.size();
+            constants.extend(pushed(&instruction, params));
+            if instruction.opcode == Opcode::Hlt {
+                continue;
+            }
+            if is_jump(&instruction) {
+                pending.extend(jump_target(codes, &instruction, params));
+                if instruction.modes[0] == Mode::Immediate && (params[0] != 0) == (instruction.opcode == Opcode::Jnz) {
+                    if pushes(codes, &straight(codes, &starts, address), next) {
+                        pending.push(next);
+                    }
+                    continue;
+                }
+            }
+            pending.push(next);
+        }
+        let overlaps = |address: usize| starts
+            .range(..address)
+            .next_back()
+            .is_some_and(|&start| start + decode_at(codes, start).unwrap().size() > address);
+        pending = constants
+            .iter()
+            .copied()
+            .filter(|&address| !starts.contains(&address) && !overlaps(address) && is_entry(codes, address))
+            .collect();
+        if pending.is_empty() {
+            return starts;
+        }
+    }
+}
+
+// Instructions running straight to the one at the address, which is included
+fn straight(codes: &[i64], starts: &BTreeSet<usize>, address: usize) -> Vec<usize> {
+    let mut instructions = vec![address];
+    while let Some(&previous) = starts.range(..instructions[instructions.len() - 1]).next_back() {
+        let instruction = decode_at(codes, previous).unwrap();
+        if previous + instruction.size() != instructions[instructions.len() - 1] || is_jump(&instruction) || instruction.opcode == Opcode::Hlt {
+            break;
+        }
+        instructions.push(previous);
+    }
+    instructions
+}
+
+// Whether one of the instructions pushes the address on the stack
+fn pushes(codes: &[i64], instructions: &[usize], address: usize) -> bool {
+    instructions.iter().any(|&start| {
+        let instruction = decode_at(codes, start).unwrap();
+        pushed(&instruction, &codes[start + 1..start + instruction.size()]) == Some(address)
+    })
+}
+
+fn is_jump(instruction: &Instruction) -> bool {
+    matches!(instruction.opcode, Opcode::Jnz | Opcode::Jz)
+}
+
+// Constant pushed on the stack, like return addresses before calls
+fn pushed(instruction: &Instruction, params: &[i64]) -> Option<usize> {
+    let value = match (instruction.opcode, instruction.modes) {
+        (Opcode::Add, [Mode::Immediate, Mode::Immediate, Mode::Relative]) => params[0].checked_add(params[1]),
+        (Opcode::Mul, [Mode::Immediate, Mode::Immediate, Mode::Relative]) => params[0].checked_mul(params[1]),
+        _ => None
+    };
+    value.and_then(|value| usize::try_from(value).ok())
+}
+
+// Target of a jump when it's a constant, targets read from memory are computed
+fn jump_target(codes: &[i64], instruction: &Instruction, params: &[i64]) -> Option<usize> {
+    match instruction.modes[1] {
+        Mode::Immediate => usize::try_from(params[1]).ok().filter(|&target| target < codes.len()),
+        Mode::Position | Mode::Relative => None
+    }
+}
+
+// Functions start by reserving their frame
+fn is_entry(codes: &[i64], address: usize) -> bool {
+    matches!(decode_at(codes, address), Some(Instruction { opcode: Opcode::Arb, modes: [Mode::Immediate, ..] }) if codes[address + 1] > 0)
+}
+
+fn exit(codes: &[i64], starts: &BTreeSet<usize>, address: usize, next: usize) -> Exit {
+    let instruction = decode_at(codes, address).unwrap();
+    let params = &codes[address + 1..next];
+    let target = jump_target(codes, &instruction, params);
+    let taken = match instruction.modes[0] {
+        Mode::Immediate => Some((params[0] != 0) == (instruction.opcode == Opcode::Jnz)),
+        _ => None
+    };
+    let call = pushes(codes, &straight(codes, starts, address), next);
+    match (taken, target) {
+        (Some(false), _) => Exit::Next(next),
+        (Some(true), target) if call => Exit::Call { target, ret: next },
+        (Some(true), Some(target)) => Exit::Jump(target),
+        (Some(true), None) if instruction.modes[1] == Mode::Relative => Exit::Return,
+        (Some(true), None) => Exit::Indirect,
+        (None, target) => Exit::Branch { target, next }
+    }
+}
+
+// Addresses written by the instruction, when they don't depend on the relative base
+fn written(instruction: &Instruction, pos: usize, params: &[i64]) -> Option<usize> {
+    let param = instruction.opcode.output_param()?;
+    match instruction.modes[param - 1] {
+        Mode::Position => usize::try_from(params[param - 1]).ok(),
+        Mode::Immediate => Some(pos + param),
+        Mode::Relative => None
+    }
+}
+
+fn accesses(instruction: &Instruction, pos: usize, params: &[i64], block: &mut Block) {
+    for (i, (&param, &mode)) in params.iter().zip(&instruction.modes).enumerate() {
+        if Some(i + 1) != instruction.opcode.output_param() && mode == Mode::Position {
+            block.reads.extend(usize::try_from(param).ok());
+        }
+    }
+    block.writes.extend(written(instruction, pos, params));
+}
+
+fn function(blocks: &BTreeMap<usize, Block>, codes: &[i64], entry: usize) -> Function {
+    let frame = match Instruction::decode(codes[entry]) {
+        Some(Instruction { opcode: Opcode::Arb, modes: [Mode::Immediate, ..] }) => Some(codes[entry + 1]),
+        _ => None
+    };
+    let mut function = Function { entry, frame, blocks: BTreeSet::new(), calls: BTreeSet::new(), pointer_calls: 0 };
+    let mut pending = vec![entry];
+    while let Some(start) = pending.pop() {
+        let block = match blocks.get(&start) {
+            Some(block) if function.blocks.insert(start) => block,
+            _ => continue
+        };
+        match block.exit {
+            Exit::Call { target: Some(target), .. } => {
+                function.calls.insert(target);
+            },
+            Exit::Call { target: None, .. } => function.pointer_calls += 1,
+            _ => ()
+        }
+        pending.extend(block.successors());
+    }
+    function
+}
+
+impl Graph {
+    pub fn instructions(&self) -> usize {
+        self.blocks.values().map(|block| block.instructions.len()).sum()
+    }
+
+    // Addresses read and written by the blocks of the function
+    pub fn data(&self, function: &Function) -> (BTreeSet<usize>, BTreeSet<usize>) {
+        let blocks = function.blocks.iter().filter_map(|start| self.blocks.get(start));
+        blocks.fold((BTreeSet::new(), BTreeSet::new()), |(mut reads, mut writes), block| {
+            reads.extend(&block.reads);
+            writes.extend(&block.writes);
+            (reads, writes)
+        })
+    }
+
+    // Functions with their frames, calls and data, followed by the self-modifying writes
+    pub fn summary(&self, codes: &[i64]) -> String {
+        let mut summary = String::new();
+        writeln!(summary, "{} words, {} instructions in {} blocks, {} functions",
+            codes.len(), self.instructions(), self.blocks.len(), self.functions.len()).unwrap();
+        for function in &self.functions {
+            write!(summary, "\nfunction {}", function.entry).unwrap();
+            if let Some(frame) = function.frame {
+                write!(summary, ", frame {}", frame).unwrap();
+            }
+            writeln!(summary, ", {} blocks", function.blocks.len()).unwrap();
+            if !function.calls.is_empty() {
+                writeln!(summary, "  calls {}", join(&function.calls)).unwrap();
+            }
+            if function.pointer_calls > 0 {
+                writeln!(summary, "  calls through pointers {} times", function.pointer_calls).unwrap();
+            }
+            let (reads, writes) = self.data(function);
+            if !reads.is_empty() {
+                writeln!(summary, "  reads {}", join(&reads)).unwrap();
+            }
+            if !writes.is_empty() {
+                writeln!(summary, "  writes {}", join(&writes)).unwrap();
+            }
+        }
+        if !self.patches.is_empty() {
+            writeln!(summary, "\nself-modifying writes:").unwrap();
+        }
+        for patch in &self.patches {
+            writeln!(summary, "  {} -> {} of {}", line_at(codes, patch.pos).to_string().trim(), patch.address,
+                line_at(codes, patch.target).to_string().trim()).unwrap();
+        }
+        summary
+    }
+
+    // Graphviz DOT, with a cluster per function and patched blocks in red
+    pub fn dot(&self, codes: &[i64]) -> String {
+        let patched = self.patches.iter().map(|patch| self.block_of(patch.target)).collect::<BTreeSet<_>>();
+        let node = |block: &Block| {
+            let label = block.instructions
+                .iter()
+                .map(|&address| format!("{}\\l", line_at(codes, address).to_string().replace('\\', "\\\\").replace('"', "\\\"")))
+                .collect::<String>();
+            let color = if patched.contains(&Some(block.start)) { ", color=red" } else { "" };
+            format!("b{} [label=\"{}\"{}];", block.start, label, color)
+        };
+
+        let mut dot = String::new();
+        writeln!(dot, "digraph intcode {{").unwrap();
+        writeln!(dot, "    node [shape=box, fontname=\"monospace\"];").unwrap();
+        let mut placed = BTreeSet::new();
+        for function in &self.functions {
+            writeln!(dot, "    subgraph cluster_{} {{", function.entry).unwrap();
+            match function.frame {
+                Some(frame) => writeln!(dot, "        label=\"function {} (frame {})\";", function.entry, frame).unwrap(),
+                None => writeln!(dot, "        label=\"function {}\";", function.entry).unwrap()
+            }
+            for start in &function.blocks {
+                if placed.insert(*start) {
+                    writeln!(dot, "        {}", node(&self.blocks[start])).unwrap();
+                }
+            }
+            writeln!(dot, "    }}").unwrap();
+        }
+        for block in self.blocks.values().filter(|block| !placed.contains(&block.start)) {
+            writeln!(dot, "    {}", node(block)).unwrap();
+        }
+        for block in self.blocks.values() {
+            let from = block.start;
+            match block.exit {
+                Exit::Next(next) | Exit::Jump(next) => writeln!(dot, "    b{} -> b{};", from, next).unwrap(),
+                Exit::Branch { target, next } => {
+                    if let Some(target) = target {
+                        writeln!(dot, "    b{} -> b{} [label=\"jump\"];", from, target).unwrap();
+                    }
+                    writeln!(dot, "    b{} -> b{} [label=\"next\"];", from, next).unwrap();
+                },
+                Exit::Call { target, ret } => {
+                    if let Some(target) = target {
+                        writeln!(dot, "    b{} -> b{} [label=\"call\", style=bold];", from, target).unwrap();
+                    }
+                    let label = if target.is_some() { "return" } else { "call through pointer" };
+                    writeln!(dot, "    b{} -> b{} [label=\"{}\", style=dashed];", from, ret, label).unwrap();
+                },
+                Exit::Return | Exit::Indirect | Exit::Halt | Exit::End => ()
+            }
+        }
+        for patch in &self.patches {
+            if let (Some(from), Some(to)) = (self.block_of(patch.pos), self.block_of(patch.target)) {
+                writeln!(dot, "    b{} -> b{} [label=\"writes {}\", style=dotted, color=red];", from, to, patch.address).unwrap();
+            }
+        }
+        writeln!(dot, "}}").unwrap();
+        dot
+    }
+
+    // Block holding the instruction at the address
+    pub fn block_of(&self, address: usize) -> Option<usize> {
+        self.blocks.range(..=address).next_back().filter(|(_, block)| block.instructions.contains(&address)).map(|(&start, _)| start)
+    }
+}
+
+fn join(addresses: &BTreeSet<usize>) -> String {
+    addresses.iter().map(|address| address.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+#[cfg(test)]
+const PROGRAM: [i64; 35] = [
+    // 0: main, calls 16 with 40, outputs a global and ends
+    109, 100, 21101, 0, 40, 1, 21101, 13, 0, 0, 1105, 1, 16, 4, 33, 99,
+    // 16: function patching OUT with the double of its argument
+    109, 2, 22102, 2, -1, -1, 1201, -1, 0, 27, 104, 0, 109, -2, 2106, 0, 0,
+    // 33: data
+    7, 8
+];
+
+#[test]
+fn test0_analysis() {
+    let graph = analyze(&PROGRAM);
+    assert_eq!(graph.blocks.keys().copied().collect::<Vec<_>>(), [0, 13, 16]);
+    assert_eq!(graph.blocks[&0].exit, Exit::Call { target: Some(16), ret: 13 });
+    assert_eq!(graph.blocks[&13].exit, Exit::Halt);
+    assert_eq!(graph.blocks[&13].reads, BTreeSet::from([33]));
+    assert_eq!(graph.blocks[&16].exit, Exit::Return);
+    assert_eq!(graph.blocks[&16].instructions, [16, 18, 22, 26, 28, 30]);
+    assert_eq!(graph.blocks[&16].writes, BTreeSet::from([27]));
+
+    assert_eq!(graph.functions.len(), 2);
+    assert_eq!(graph.functions[0], Function { entry: 0, frame: Some(100), blocks: BTreeSet::from([0, 13]), calls: BTreeSet::from([16]), pointer_calls: 0 });
+    assert_eq!(graph.functions[1].frame, Some(2));
+    assert_eq!(graph.patches, [Patch { pos: 22, address: 27, target: 26 }]);
+    assert_eq!(graph.block_of(28), Some(16));
+    assert_eq!(graph.block_of(29), None);
+}
+
+#[test]
+fn test1_analysis() {
+    let graph = analyze(&PROGRAM);
+    assert_eq!(graph.summary(&PROGRAM), "\
+35 words, 12 instructions in 3 blocks, 2 functions
+
+function 0, frame 100, 2 blocks
+  calls 16
+  reads 33
+
+function 16, frame 2, 1 blocks
+  writes 27
+
+self-modifying writes:
+  22: ADD  @-1, #0, 27 -> 27 of 26: OUT  #0
+");
+
+    let dot = graph.dot(&PROGRAM);
+    assert!(dot.starts_with("digraph intcode {\n    node [shape=box, fontname=\"monospace\"];\n    subgraph cluster_0 {\n        label=\"function 0 (frame 100)\";\n"));
+    assert!(dot.contains("        b13 [label=\"   13: OUT  33\\l   15: HLT\\l\"];\n"));
+    assert!(dot.contains("    b0 -> b16 [label=\"call\", style=bold];\n    b0 -> b13 [label=\"return\", style=dashed];\n"));
+    assert!(dot.contains("        b16 [label=\"   16: ARB  #2\\l"));
+    assert!(dot.contains("   30: JZ   #0, @0\\l\", color=red];\n"));
+    assert!(dot.ends_with("    b16 -> b16 [label=\"writes 27\", style=dotted, color=red];\n}\n"));
+}
+
+#[test]
+fn test2_analysis() {
+    // the function at 7 is only known from a pointer to it
+    let graph = analyze(&[109, 50, 21101, 7, 0, 1, 99, 109, 1, 109, -1, 2106, 0, 0]);
+    assert_eq!(graph.functions.iter().map(|function| (function.entry, function.frame)).collect::<Vec<_>>(), [(0, Some(50)), (7, Some(1))]);
+
+    // the function at 14 calls the one at 28 through the pointer it's given
+    let codes = [
+        109, 50, 21101, 28, 0, 1, 21101, 13, 0, 0, 1105, 1, 14, 99,
+        109, 2, 21101, 23, 0, 0, 2105, 1, -1, 109, -2, 2105, 1, 0,
+        109, 1, 109, -1, 2106, 0, 0
+    ];
+    let graph = analyze(&codes);
+    assert_eq!(graph.blocks[&14].exit, Exit::Call { target: None, ret: 23 });
+    assert_eq!(graph.blocks[&23].exit, Exit::Return);
+    assert_eq!(graph.functions.iter().map(|function| function.entry).collect::<Vec<_>>(), [0, 14, 28]);
+    assert_eq!(graph.functions[1].pointer_calls, 1);
+    assert_eq!(graph.functions[1].blocks, BTreeSet::from([14, 23]));
+}
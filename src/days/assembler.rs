@@ -0,0 +1,332 @@
+//
+// Assembler for the IntCode instruction set
+//
+// Operands use the syntax of the disassembler listing: position mode by default,
+// `#` for immediate mode and `@` for relative mode. On top of that it supports
+//   - labels (`loop:`), usable wherever a value is expected as `loop`, `loop+2`, `loop-1`
+//   - numeric labels (`12:`), which check the current address, so listings assemble back
+//   - data blocks (`.data 1, -2, label, "text\n"`), `DATA` is accepted as well
+//   - macros (`.macro name p1, p2` ... `.endm`), with parameters referenced as `\p1`
+//     and `\@` expanding to a number unique to each expansion, for local labels
+//   - comments, from `;` to the end of the line
+//
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use lazy_static::lazy_static;
+use regex::Regex;
+use crate::intcode::{ Instruction, Mode, Opcode };
+
+const MAX_MACRO_DEPTH: usize = 64;
+
+#[derive(Debug, PartialEq)]
+pub struct AssemblerError {
+    pub line: usize,
+    pub message: String
+}
+
+impl fmt::Display for AssemblerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for AssemblerError {}
+
+struct Macro {
+    line: usize,
+    params: Vec<String>,
+    body: Vec<String>
+}
+
+enum Word {
+    Value(i64),
+    Expression(String)
+}
+
+pub fn assemble(source: &str) -> Result<Vec<i64>, AssemblerError> {
+    lazy_static! {
+        static ref LABEL_RE: Regex = Regex::new(r"^\s*([A-Za-z_.][\w.]*|\d+):").unwrap();
+    }
+
+    let mut macros: HashMap<String, Macro> = HashMap::new();
+    let mut labels: HashMap<String, i64> = HashMap::new();
+    let mut words: Vec<(usize, Word)> = Vec::new();
+    let mut defining: Option<(String, Macro)> = None;
+    let mut expansions = 0;
+    let mut pending = source
+        .lines()
+        .enumerate()
+        .map(|(i, text)| (i + 1, text.to_string(), 0))
+        .collect::<Vec<_>>();
+    pending.reverse();
+
+    while let Some((line, text, depth)) = pending.pop() {
+        let error = |message: String| AssemblerError { line, message };
+        let mut text = strip_comment(&text).trim();
+
+        if let Some((_, definition)) = &mut defining {
+            if text.eq_ignore_ascii_case(".endm") {
+                let (name, definition) = defining.take().unwrap();
+                macros.insert(name, definition);
+            } else {
+                definition.body.push(text.to_string());
+            }
+            continue;
+        }
+
+        while let Some(captures) = LABEL_RE.captures(text) {
+            let label = captures.get(1).unwrap().as_str();
+            let address = words.len() as i64;
+            if let Ok(expected) = label.parse::<i64>() {
+                if expected != address {
+                    return Err(error(format!("address {} expected but current address is {}", expected, address)));
+                }
+            } else if labels.insert(label.to_string(), address).is_some() {
+                return Err(error(format!("label {} already defined", label)));
+            }
+            text = text[captures.get(0).unwrap().end()..].trim();
+        }
+
+        let (head, rest) = text.split_at(text.find(char::is_whitespace).unwrap_or(text.len()));
+        let operands = split_operands(rest.trim()).map_err(error)?;
+
+        if head.is_empty() {
+            continue;
+        } else if head.eq_ignore_ascii_case(".macro") {
+            let name = operands.first().and_then(|o| o.split_whitespace().next())
+                .ok_or_else(|| error("missing macro name".to_string()))?;
+            let mut params = operands[0].split_whitespace().skip(1).map(|p| p.to_string()).collect::<Vec<_>>();
+            params.extend(operands.iter().skip(1).map(|p| p.to_string()));
+            defining = Some((name.to_string(), Macro { line, params, body: Vec::new() }));
+        } else if head.eq_ignore_ascii_case(".endm") {
+            return Err(error(".endm without .macro".to_string()));
+        } else if head.eq_ignore_ascii_case(".data") || head == "DATA" {
+            for operand in operands {
+                if operand.starts_with('"') {
+                    let string = parse_string(&operand).map_err(error)?;
+                    words.extend(string.bytes().map(|b| (line, Word::Value(b as i64))));
+                } else {
+                    words.push((line, Word::Expression(operand)));
+                }
+            }
+        } else if let Some(opcode) = Opcode::from_mnemonic(head) {
+            if operands.len() != opcode.params() {
+                return Err(error(format!("{} expects {} operands, found {}", opcode.mnemonic(), opcode.params(), operands.len())));
+            }
+            let mut modes = [Mode::Position; 3];
+            let mut params = Vec::new();
+            for (operand, mode) in operands.iter().zip(modes.iter_mut()) {
+                let (prefix, expression) = match operand.chars().next() {
+                    Some('#') => (Mode::Immediate, &operand[1..]),
+                    Some('@') => (Mode::Relative, &operand[1..]),
+                    _ => (Mode::Position, operand.as_str())
+                };
+                *mode = prefix;
+                params.push((line, Word::Expression(expression.to_string())));
+            }
+            words.push((line, Word::Value(Instruction::new(opcode, modes).encode())));
+            words.extend(params);
+        } else if let Some(definition) = macros.get(head) {
+            if operands.len() != definition.params.len() {
+                return Err(error(format!("macro {} expects {} arguments, found {}", head, definition.params.len(), operands.len())));
+            }
+            if depth >= MAX_MACRO_DEPTH {
+                return Err(error(format!("macro {} nested too deeply", head)));
+            }
+            expansions += 1;
+            // longest parameters first, so that \ab is not replaced as \a followed by b
+            let mut arguments = definition.params.iter().zip(operands.iter()).collect::<Vec<_>>();
+            arguments.sort_by_key(|(param, _)| std::cmp::Reverse(param.len()));
+            for body in definition.body.iter().rev() {
+                let expanded = arguments
+                    .iter()
+                    .fold(body.replace("\\@", &expansions.to_string()), |text, (param, arg)|
+                        text.replace(&format!("\\{}", param), arg)
+                    );
+                pending.push((line, expanded, depth + 1));
+            }
+        } else {
+            return Err(error(format!("unknown instruction or macro {}", head)));
+        }
+    }
+
+    if let Some((name, definition)) = defining {
+        return Err(AssemblerError { line: definition.line, message: format!("macro {} without .endm", name) });
+    }
+
+    words
+        .into_iter()
+        .map(|(line, word)| match word {
+            Word::Value(value) => Ok(value),
+            Word::Expression(expression) => evaluate(&expression, &labels)
+                .map_err(|message| AssemblerError { line, message })
+        })
+        .collect()
+}
+
+fn strip_comment(text: &str) -> &str {
+    let mut quoted = false;
+    let mut escaped = false;
+    for (i, c) in text.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            ';' if !quoted => return &text[..i],
+            _ => ()
+        }
+    }
+    text
+}
+
+fn split_operands(text: &str) -> Result<Vec<String>, String> {
+    let mut operands = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut escaped = false;
+    for c in text.chars() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            ',' if !quoted => {
+                operands.push(current.trim().to_string());
+                current.clear();
+                continue;
+            },
+            _ => ()
+        }
+        current.push(c);
+    }
+    if quoted {
+        return Err(format!("unterminated string {}", current.trim()));
+    }
+    if !current.trim().is_empty() || !operands.is_empty() {
+        operands.push(current.trim().to_string());
+    }
+    match operands.iter().find(|o| o.is_empty()) {
+        Some(_) => Err(format!("empty operand in {}", text)),
+        None => Ok(operands)
+    }
+}
+
+fn parse_string(operand: &str) -> Result<String, String> {
+    if operand.len() < 2 || !operand.ends_with('"') {
+        return Err(format!("wrong string {}", operand));
+    }
+    let mut string = String::new();
+    let mut chars = operand[1..operand.len() - 1].chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n') => string.push('\n'),
+                Some('t') => string.push('\t'),
+                Some('\\') => string.push('\\'),
+                Some('"') => string.push('"'),
+                _ => return Err(format!("wrong escape sequence in {}", operand))
+            }
+        } else {
+            string.push(c);
+        }
+    }
+    Ok(string)
+}
+
+fn evaluate(expression: &str, labels: &HashMap<String, i64>) -> Result<i64, String> {
+    lazy_static! {
+        static ref TERM_RE: Regex = Regex::new(r"^\s*([+-]?)\s*(-?\d+|[A-Za-z_.][\w.]*)\s*").unwrap();
+    }
+
+    let mut value: i64 = 0;
+    let mut rest = expression;
+    let mut first = true;
+    while !rest.is_empty() {
+        let captures = TERM_RE.captures(rest)
+            .filter(|c| first || !c[1].is_empty())
+            .ok_or_else(|| format!("wrong expression {}", expression))?;
+        let term = &captures[2];
+        let term_value = match term.parse::<i64>() {
+            Ok(number) => number,
+            Err(_) => *labels.get(term).ok_or_else(|| format!("undefined label {}", term))?
+        };
+        value = if &captures[1] == "-" { value.checked_sub(term_value) } else { value.checked_add(term_value) }
+            .ok_or_else(|| format!("overflow in expression {}", expression))?;
+        rest = &rest[captures.get(0).unwrap().end()..];
+        first = false;
+    }
+    if first {
+        return Err("empty expression".to_string());
+    }
+    Ok(value)
+}
+
+#[test]
+fn test0_assemble() {
+    let source = "
+        ; compare input with 8
+                IN   value
+                EQ   value, eight, value
+                OUT  value
+                HLT
+        value:  .data -1
+        eight:  .data 8
+    ";
+    assert_eq!(assemble(source), Ok(vec![3,9,8,9,10,9,4,9,99,-1,8]));
+}
+
+#[test]
+fn test1_assemble() {
+    let source = "
+        .macro push value
+                ADD  \\value, #0, @0
+                ARB  #1
+        .endm
+        .macro repeat count
+                ADD  #\\count, #0, n\\@
+        loop\\@: JZ   n\\@, #end\\@
+                OUT  #42
+                ADD  n\\@, #-1, n\\@
+                JNZ  #1, #loop\\@
+        n\\@:    .data 0
+        end\\@:
+        .endm
+                ARB  #stack
+                push #3
+                repeat 2
+                HLT
+        stack:
+    ";
+    assert_eq!(assemble(source), Ok(vec![
+        109,26,
+        21101,3,0,0, 109,1,
+        1101,2,0,24, 1006,24,25, 104,42, 1001,24,-1,24, 1105,1,12, 0,
+        99
+    ]));
+}
+
+#[test]
+fn test2_assemble() {
+    let source = "
+        OUT #text
+        OUT #text+1
+        HLT
+        text: .data \"Hi\\n\", end - text
+        end:
+    ";
+    assert_eq!(assemble(source), Ok(vec![104,5,104,6,99,72,105,10,4]));
+}
+
+#[test]
+fn test3_assemble() {
+    let codes = vec![109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99];
+    assert_eq!(assemble(&crate::disassembler::listing(&codes)), Ok(codes));
+}
+
+#[test]
+fn test4_assemble() {
+    assert_eq!(assemble("ADD #1, 2"), Err(AssemblerError { line: 1, message: "ADD expects 3 operands, found 2".to_string() }));
+    assert_eq!(assemble("\nJNZ #1, #missing"), Err(AssemblerError { line: 2, message: "undefined label missing".to_string() }));
+    assert_eq!(assemble("HLT\n0: HLT"), Err(AssemblerError { line: 2, message: "address 0 expected but current address is 1".to_string() }));
+    assert_eq!(assemble(".macro m\nHLT"), Err(AssemblerError { line: 1, message: "macro m without .endm".to_string() }));
+}
@@ -0,0 +1,283 @@
+//
+// Ahead of time translation of IntCode programs to Rust
+//
+// Instructions reachable from address 0 become the arms of a match on the position,
+// with parameters and modes resolved at translation time, except for the parameters
+// the program patches itself. The generated code gives control back to the interpreter
+// for a single instruction whenever it can't go on by itself:
+//   - jumps to code that was not translated
+//   - other writes to translated code, after which the program is only interpreted
+//   - anything the interpreter reports as an error, like overflows and negative addresses
+// The generated module has to be placed where `crate::compiler`, `crate::devices` and
+// `crate::intcode` are available, like the translation of day 19 in `src/days/day19/beam.rs`,
+// made with the compile command.
+// Only the instructions left to the interpreter go through it: the ones run by compiled
+// code are not bound by the Limits of the program, and neither feed its Tracer nor are
+// recorded in its History or Session
+//
+use std::collections::BTreeSet;
+use std::convert::TryFrom;
+use std::fmt::Write;
+use crate::devices::Device;
+use crate::disassembler::{ line_at, reachable };
+use crate::intcode::{ Instruction, IntCode, IntCodeError, Mode, Opcode, Status };
+
+const VALUES_PER_LINE: usize = 16;
+
+pub struct Compiled<D> {
+    // program the code was translated from
+    pub codes: &'static [i64],
+    // words of the translated instructions, parameters included
+    pub code: &'static [bool],
+    // executes the instruction at the position, returning the next position or a status
+    // that ends the run, Running meaning that the interpreter has to take over
+    pub execute: fn(&mut IntCode<D>, usize, &mut i64) -> Result<usize, Status>
+}
+
+impl<D: Device> Compiled<D> {
+    // Runs the compiled code while the program is still the translated one, the interpreter
+    // executes the instructions the compiled code gives up on
+    pub fn process(&self, program: &mut IntCode<D>) -> Result<Status, IntCodeError> {
+        while self.matches(program) {
+            let mut pos = program.pos();
+            let mut base = program.base();
+            let status = loop {
+                match (self.execute)(program, pos, &mut base) {
+                    Ok(next) => pos = next,
+                    Err(status) => break status
+                }
+            };
+            program.set_registers(pos, base);
+            match status {
+                Status::Running => match program.step()? {
+                    Status::Running => (),
+                    status => return Ok(status)
+                },
+                status => return Ok(status)
+            }
+        }
+        program.process()
+    }
+
+    fn matches(&self, program: &IntCode<D>) -> bool {
+        self.code
+            .iter()
+            .zip(self.codes)
+            .enumerate()
+            .all(|(address, (&code, &value))| !code || program.peek(address) == value)
+    }
+}
+
+// Helpers of the generated code, errors give control back to the interpreter
+
+pub fn relative(base: i64, offset: i64) -> Result<usize, Status> {
+    base.checked_add(offset).ok_or(Status::Running).and_then(address)
+}
+
+pub fn address(value: i64) -> Result<usize, Status> {
+    if value < 0 { Err(Status::Running) } else { Ok(value as usize) }
+}
+
+pub fn writable(code: &[bool], address: usize) -> Result<usize, Status> {
+    if code.get(address) == Some(&true) { Err(Status::Running) } else { Ok(address) }
+}
+
+// Source of a module exposing `compiled()`, which gives the Compiled version of the program
+pub fn translate(codes: &[i64]) -> String {
+    let starts = reachable(codes);
+    let mut code = vec![false; codes.len()];
+    for &start in &starts {
+        let size = Instruction::decode(codes[start]).map_or(1, |i| i.size());
+        code[start..start + size].iter_mut().for_each(|c| *c = true);
+    }
+    // Parameters patched by the program itself are read from memory instead
+    let patched = starts
+        .iter()
+        .filter_map(|&start| {
+            let instruction = Instruction::decode(codes[start])?;
+            let param = instruction.opcode.output_param()?;
+            if instruction.modes[param - 1] != Mode::Position {
+                return None;
+            }
+            usize::try_from(codes[start + param]).ok()
+        })
+        .filter(|&address| code.get(address) == Some(&true) && !starts.contains(&address))
+        .collect::<BTreeSet<_>>();
+    patched.iter().for_each(|&address| code[address] = false);
+
+    let mut source = String::new();
+    writeln!(source, "// Translated from an IntCode program of {} words, do not edit", codes.len()).unwrap();
+    writeln!(source, "use crate::compiler::{{ self, Compiled }};").unwrap();
+    writeln!(source, "use crate::devices::Device;").unwrap();
+    writeln!(source, "use crate::intcode::{{ IntCode, Status }};\n").unwrap();
+    writeln!(source, "const CODES: &[i64] = &[{}];\n", lines(codes.iter().map(|c| c.to_string()))).unwrap();
+    writeln!(source, "const CODE: &[bool] = &[{}];\n", lines(code.iter().map(|c| c.to_string()))).unwrap();
+    writeln!(source, "pub fn compiled<D: Device>() -> Compiled<D> {{").unwrap();
+    writeln!(source, "    Compiled {{ codes: CODES, code: CODE, execute }}").unwrap();
+    writeln!(source, "}}\n").unwrap();
+    writeln!(source, "#[allow(unused_variables, clippy::all)]").unwrap();
+    writeln!(source, "fn execute<D: Device>(program: &mut IntCode<D>, pos: usize, base: &mut i64) -> Result<usize, Status> {{").unwrap();
+    writeln!(source, "    match pos {{").unwrap();
+    for &start in &starts {
+        let instruction = Instruction::decode(codes[start]).unwrap();
+        let params = &codes[start + 1..start + instruction.size()];
+        writeln!(source, "        // {}", line_at(codes, start).to_string().trim()).unwrap();
+        writeln!(source, "        {} => {{", start).unwrap();
+        for statement in translate_instruction(start, &instruction, params, &patched) {
+            writeln!(source, "            {}", statement).unwrap();
+        }
+        writeln!(source, "        }},").unwrap();
+    }
+    writeln!(source, "        _ => Err(Status::Running)").unwrap();
+    writeln!(source, "    }}").unwrap();
+    writeln!(source, "}}").unwrap();
+    source
+}
+
+fn lines(values: impl Iterator<Item = String>) -> String {
+    let values = values.collect::<Vec<_>>();
+    if values.is_empty() {
+        return String::new();
+    }
+    let lines = values.chunks(VALUES_PER_LINE).map(|chunk| chunk.join(", ")).collect::<Vec<_>>();
+    format!("\n    {}\n", lines.join(",\n    "))
+}
+
+fn translate_instruction(pos: usize, instruction: &Instruction, params: &[i64], patched: &BTreeSet<usize>) -> Vec<String> {
+    let bail = || vec!["Err(Status::Running)".to_string()];
+    // value of the parameter and address it points to in position mode
+    let mut operands = Vec::new();
+    for (i, &param) in params.iter().enumerate() {
+        let address = pos + i + 1;
+        operands.push(if patched.contains(&address) {
+            let value = format!("program.peek({})", address);
+            let position = format!("compiler::address({})?", value);
+            (value, Some(position))
+        } else {
+            (param.to_string(), usize::try_from(param).ok().map(|p| p.to_string()))
+        });
+    }
+
+    let mut reads = Vec::new();
+    for (i, ((value, position), &mode)) in operands.iter().zip(&instruction.modes).enumerate() {
+        let read = match (mode, position) {
+            _ if Some(i + 1) == instruction.opcode.output_param() => continue,
+            (Mode::Immediate, _) => value.clone(),
+            (Mode::Position, Some(position)) => format!("program.peek({})", position),
+            (Mode::Position, None) => return bail(),
+            (Mode::Relative, _) => format!("program.peek(compiler::relative(*base, {})?)", value)
+        };
+        reads.push(read);
+    }
+    let write = match instruction.opcode.output_param() {
+        Some(i) => match (instruction.modes[i - 1], &operands[i - 1]) {
+            // immediate writes go to the instruction itself
+            (Mode::Immediate, _) => return bail(),
+            (Mode::Position, (_, Some(position))) => format!("let address = compiler::writable(CODE, {})?;", position),
+            (Mode::Position, (_, None)) => return bail(),
+            (Mode::Relative, (value, _)) => format!("let address = compiler::writable(CODE, compiler::relative(*base, {})?)?;", value)
+        },
+        None => String::new()
+    };
+    let values = reads
+        .iter()
+        .zip(&["x", "y"])
+        .map(|(read, name)| format!("let {}: i64 = {};", name, read))
+        .collect::<Vec<_>>();
+    let next = pos + instruction.size();
+
+    let body = match instruction.opcode {
+        Opcode::Add => vec!["program.poke(address, x.checked_add(y).ok_or(Status::Running)?);".to_string()],
+        Opcode::Mul => vec!["program.poke(address, x.checked_mul(y).ok_or(Status::Running)?);".to_string()],
+        Opcode::Lt => vec!["program.poke(address, (x < y) as i64);".to_string()],
+        Opcode::Eq => vec!["program.poke(address, (x == y) as i64);".to_string()],
+        Opcode::In => vec![
+            "let input = program.device_mut().input().ok_or(Status::Waiting)?;".to_string(),
+            "program.poke(address, input);".to_string()
+        ],
+        Opcode::Out => vec!["program.device_mut().output(x);".to_string()],
+        Opcode::Arb => vec!["*base = base.checked_add(x).ok_or(Status::Running)?;".to_string()],
+        Opcode::Jnz => return [values, vec![format!("if x != 0 {{ compiler::address(y) }} else {{ Ok({}) }}", next)]].concat(),
+        Opcode::Jz => return [values, vec![format!("if x == 0 {{ compiler::address(y) }} else {{ Ok({}) }}", next)]].concat(),
+        Opcode::Hlt => return vec!["Err(Status::End)".to_string()]
+    };
+    let mut statements = Vec::new();
+    if !write.is_empty() {
+        statements.push(write);
+    }
+    statements.extend(values);
+    statements.extend(body);
+    statements.push(format!("Ok({})", next));
+    statements
+}
+
+#[test]
+fn test0_translate() {
+    let source = translate(&[3,9,8,9,10,9,4,9,99,-1,8]);
+    assert_eq!(source, "\
+// Translated from an IntCode program of 11 words, do not edit
+use crate::compiler::{ self, Compiled };
+use crate::devices::Device;
+use crate::intcode::{ IntCode, Status };
+
+const CODES: &[i64] = &[
+    3, 9, 8, 9, 10, 9, 4, 9, 99, -1, 8
+];
+
+const CODE: &[bool] = &[
+    true, true, true, true, true, true, true, true, true, false, false
+];
+
+pub fn compiled<D: Device>() -> Compiled<D> {
+    Compiled { codes: CODES, code: CODE, execute }
+}
+
+#[allow(unused_variables, clippy::all)]
+fn execute<D: Device>(program: &mut IntCode<D>, pos: usize, base: &mut i64) -> Result<usize, Status> {
+    match pos {
+        // 0: IN   9
+        0 => {
+            let address = compiler::writable(CODE, 9)?;
+            let input = program.device_mut().input().ok_or(Status::Waiting)?;
+            program.poke(address, input);
+            Ok(2)
+        },
+        // 2: EQ   9, 10, 9
+        2 => {
+            let address = compiler::writable(CODE, 9)?;
+            let x: i64 = program.peek(9);
+            let y: i64 = program.peek(10);
+            program.poke(address, (x == y) as i64);
+            Ok(6)
+        },
+        // 6: OUT  9
+        6 => {
+            let x: i64 = program.peek(9);
+            program.device_mut().output(x);
+            Ok(8)
+        },
+        // 8: HLT
+        8 => {
+            Err(Status::End)
+        },
+        _ => Err(Status::Running)
+    }
+}
+");
+}
+
+#[test]
+fn test1_translate() {
+    // immediate writes and negative addresses are left to the interpreter
+    let source = translate(&[11101,1,2,3,1,-1,0,0,99]);
+    assert!(source.contains("        0 => {\n            Err(Status::Running)\n        },"));
+    assert!(source.contains("        4 => {\n            Err(Status::Running)\n        },"));
+}
+
+#[test]
+fn test2_translate() {
+    // the parameter of OUT is patched by ADD, so it is read from memory
+    let source = translate(&[1101,7,0,5,104,0,99]);
+    assert!(source.contains("    true, true, true, true, true, false, true\n"));
+    assert!(source.contains("        4 => {\n            let x: i64 = program.peek(5);\n"));
+}
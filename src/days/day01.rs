@@ -1,21 +1,15 @@
-use std::fs;
+use crate::Answer;
 
-pub fn first_star() {
-    let contents = fs::read_to_string("./input/day01.txt")
-        .expect("Something went wrong reading the file");
+pub fn first_star(contents: &str) -> Answer {
+    let fuel = impl_first_star(contents);
 
-    let fuel = impl_first_star(&contents);
-
-    println!("day  1.1 - sum of the fuel requirements: {}", fuel);
+    Answer::from(fuel)
 }
 
-pub fn second_star() {
-    let contents = fs::read_to_string("./input/day01.txt")
-        .expect("Something went wrong reading the file");
-
-    let fuel = impl_second_star(&contents);
+pub fn second_star(contents: &str) -> Answer {
+    let fuel = impl_second_star(contents);
 
-    println!("day  1.2 - sum of the fuel requirements: {}", fuel);
+    Answer::from(fuel)
 }
 
 fn impl_first_star(contents: &str) -> u32 {
@@ -1,4 +1,4 @@
-use std::fs;
+use crate::Answer;
 
 #[derive(Debug)]
 struct PipeSlice {
@@ -11,22 +11,16 @@ struct PipeSlice {
 
 type Pipe = Vec<PipeSlice>;
 
-pub fn first_star() {
-     let contents = fs::read_to_string("./input/day03.txt")
-         .expect("Something went wrong reading the file");
+pub fn first_star(contents: &str) -> Answer {
+    let res = impl_first_star(contents);
 
-    let res = impl_first_star(&contents);
-
-    println!("day  3.1 - manhattan distance to closest intersection: {:?}", res);
+    Answer::from(res)
 }
 
-pub fn second_star() {
-    let contents = fs::read_to_string("./input/day03.txt")
-        .expect("Something went wrong reading the file");
-
-    let res = impl_second_star(&contents);
+pub fn second_star(contents: &str) -> Answer {
+    let res = impl_second_star(contents);
 
-    println!("day  3.2 - fewest combined steps to reach an intersection: {:?}", res);
+    Answer::from(res)
 }
 
 fn compute_pipes(contents: &str) -> Vec<Pipe> {
@@ -1,21 +1,15 @@
-use std::fs;
+use crate::Answer;
 
-pub fn first_star() {
-    let contents = fs::read_to_string("./input/day04.txt")
-        .expect("Something went wrong reading the file");
+pub fn first_star(contents: &str) -> Answer {
+    let count = check(contents, &check1);
 
-    let count = check(&contents, &check1);
-
-    println!("day  4.1 - count of different passwords: {}", count);
+    Answer::from(count)
 }
 
-pub fn second_star() {
-    let contents = fs::read_to_string("./input/day04.txt")
-        .expect("Something went wrong reading the file");
-
-    let count = check(&contents, &check2);
+pub fn second_star(contents: &str) -> Answer {
+    let count = check(contents, &check2);
 
-    println!("day  4.2 - count of different passwords: {}", count);
+    Answer::from(count)
 }
 
 fn check(contents: &str, check_fun: &dyn Fn(&str) -> bool) -> u32 {
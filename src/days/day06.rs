@@ -1,23 +1,21 @@
 #![allow(clippy::clone_double_ref)]
-use std::fs;
 use std::collections::BTreeMap;
+use crate::Answer;
 
-pub fn first_star() {
-    let contents = fs::read_to_string("./input/day06.txt")
-        .expect("Something went wrong reading the file");
+pub fn first_star(contents: &str) -> Answer {
+    let count = impl_first_star(contents);
 
-    let count = impl_first_star(&contents);
-
-    println!("day  6.1 - total number of direct and indirect orbits: {}", count);
+    Answer::from(count)
 }
 
-pub fn second_star() {
-    let contents = fs::read_to_string("./input/day06.txt")
-        .expect("Something went wrong reading the file");
+pub fn second_star(contents: &str) -> Answer {
+    let count = impl_second_star(contents, "YOU", "SAN");
 
-    let count = impl_second_star(&contents, "YOU", "SAN");
+    Answer::from(count)
+}
 
-    println!("day  6.2 - minimum number of orbital transfers: {}", count);
+pub fn parse(contents: &str) {
+    std::hint::black_box(extract_orbits(contents));
 }
 
 fn extract_orbits(contents: &str) -> Vec<(&str, &str)> {
@@ -1,21 +1,15 @@
-use std::fs;
+use crate::Answer;
 
-pub fn first_star() {
-    let contents = fs::read_to_string("./input/day08.txt")
-        .expect("Something went wrong reading the file");
+pub fn first_star(contents: &str) -> Answer {
+    let checksum = impl_first_star(contents, 25, 6);
 
-    let checksum = impl_first_star(&contents, 25, 6);
-
-    println!("day  8.1 - number of 1 digits multiplied by the number of 2 digits: {}", checksum);
+    Answer::from(checksum)
 }
 
-pub fn second_star() {
-    let contents = fs::read_to_string("./input/day08.txt")
-        .expect("Something went wrong reading the file");
-
-    let image = impl_second_star(&contents, 25, 6);
+pub fn second_star(contents: &str) -> Answer {
+    let image = impl_second_star(contents, 25, 6);
 
-    println!("day  8.2 - message is produced after decoding image: \n{}", image);
+    Answer::Image(image)
 }
 
 fn impl_first_star(contents: &str, width: usize, height: usize) -> usize {
@@ -1,25 +1,23 @@
-use std::fs;
 use std::f64;
 use std::collections::BTreeSet;
 use std::collections::BTreeMap;
+use crate::Answer;
 
-pub fn first_star() {
-    let contents = fs::read_to_string("./input/day10.txt")
-        .expect("Something went wrong reading the file");
+pub fn first_star(contents: &str) -> Answer {
+    let (_, count) = impl_first_star(contents);
 
-    let (_, count) = impl_first_star(&contents);
-
-    println!("day 10.1 - number of asteroids detected: {}", count);
+    Answer::from(count)
 }
 
-pub fn second_star() {
-    let contents = fs::read_to_string("./input/day10.txt")
-        .expect("Something went wrong reading the file");
-
-    let vaporized = impl_second_star(&contents);
+pub fn second_star(contents: &str) -> Answer {
+    let vaporized = impl_second_star(contents);
     let (x, y) = vaporized.get(199).unwrap();
 
-    println!("day 10.2 - coordinates of 200th vaporized asteroid (100 * x + y): {}", 100 * x + y);
+    Answer::from(100 * x + y)
+}
+
+pub fn parse(contents: &str) {
+    std::hint::black_box(extract(contents));
 }
 
 fn impl_first_star(contents: &str) -> ((i32, i32), usize) {
@@ -1,24 +1,18 @@
-use std::fs;
 use std::collections::HashMap;
+use crate::Answer;
 use crate::intcode::{ IntCode, Status, extract_codes };
 
-pub fn first_star() {
-    let contents = fs::read_to_string("./input/day11.txt")
-        .expect("Something went wrong reading the file");
+pub fn first_star(contents: &str) -> Answer {
+    let panels = paint(contents, 0);
 
-    let panels = paint(&contents, 0);
-
-    println!("day 11.1 - number of panels painted at least once: {}", panels.len());
+    Answer::from(panels.len())
 }
 
-pub fn second_star() {
-    let contents = fs::read_to_string("./input/day11.txt")
-        .expect("Something went wrong reading the file");
-
-    let panels = paint(&contents, 1);
+pub fn second_star(contents: &str) -> Answer {
+    let panels = paint(contents, 1);
     let identifier = draw(&panels);
 
-    println!("day 11.2 - painted registration identifier:\n{}", identifier);
+    Answer::Image(identifier)
 }
 
 fn paint(contents: &str, start_color: i64) -> HashMap<(i32, i32), i64> {
@@ -26,11 +20,11 @@ fn paint(contents: &str, start_color: i64) -> HashMap<(i32, i32), i64> {
     let mut direction = (0, -1);
     let mut current_color = start_color;
     let mut status = Status::Running;
-    let mut robot = IntCode::new(extract_codes(&contents));
+    let mut robot = IntCode::new(extract_codes(&contents).unwrap());
     let mut panels = HashMap::new();
     while status != Status::End {
         robot.write_one(current_color);
-        status = robot.process();
+        status = robot.process().unwrap();
         if status != Status::End {
             let new_color = robot.read_one().unwrap();
             let rotation = robot.read_one().unwrap();
@@ -1,8 +1,8 @@
-use std::fs;
 use std::cmp::Ordering;
 use lazy_static::lazy_static;
 use regex::Regex;
 use num_integer::lcm;
+use crate::Answer;
 
 #[derive(Clone, Debug, PartialEq)]
 struct Moon {
@@ -10,20 +10,18 @@ struct Moon {
     velocity: [i32; 3]
 }
 
-pub fn first_star() {
-    let contents = fs::read_to_string("./input/day12.txt")
-        .expect("Something went wrong reading the file");
-
-    let c = impl_first_star(&contents, 1000);
-    println!("day 12.1 - total energy after 1000 steps: {}", c);
+pub fn first_star(contents: &str) -> Answer {
+    let c = impl_first_star(contents, 1000);
+    Answer::from(c)
 }
 
-pub fn second_star() {
-    let contents = fs::read_to_string("./input/day12.txt")
-        .expect("Something went wrong reading the file");
+pub fn second_star(contents: &str) -> Answer {
+    let steps = impl_second_star(contents);
+    Answer::from(steps)
+}
 
-    let steps = impl_second_star(&contents);
-    println!("day 12.2 - steps to reach first state: {}", steps);
+pub fn parse(contents: &str) {
+    std::hint::black_box(extract_coordinates(contents));
 }
 
 fn impl_first_star(contents: &str, steps: usize) -> i32 {
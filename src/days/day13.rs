@@ -1,39 +1,61 @@
-use std::fs;
-use crate::intcode::{ IntCode, Status, extract_codes };
+use crate::Answer;
+use crate::devices::Device;
+use crate::intcode::{ IntCode, extract_codes };
+use crate::session::Session;
 
-pub fn first_star() {
-    let contents = fs::read_to_string("./input/day13.txt")
-        .expect("Something went wrong reading the file");
-
-    let mut game = IntCode::new(extract_codes(&contents));
-    game.process();
+pub fn first_star(contents: &str) -> Answer {
+    let mut game = IntCode::new(extract_codes(contents).unwrap());
+    game.process().unwrap();
     let output = game.read();
     let count = output.chunks(3).filter(|c| c[2] == 2).count();
 
-    println!("day 13.1 - num of block tiles on the screen when the game exits: {}", count);
+    Answer::from(count)
 }
 
-pub fn second_star() {
-    let contents = fs::read_to_string("./input/day13.txt")
-        .expect("Something went wrong reading the file");
+pub fn second_star(contents: &str) -> Answer {
+    let mut game = arcade(contents);
+    game.process().unwrap();
+    let score = game.device().score;
+
+    Answer::from(score)
+}
+
+// Session of the game played for the second star, to replay it
+pub fn session(contents: &str) -> Session {
+    let mut game = arcade(contents);
+    game.set_session(Session { memory: vec![(0, 2)], entries: Vec::new() });
+    game.process().unwrap();
+    game.take_session().unwrap()
+}
 
-    let mut game = IntCode::new(extract_codes(&contents));
+// Game played for free, with 2 quarters
+fn arcade(contents: &str) -> IntCode<Arcade> {
+    let mut game = IntCode::with_device(extract_codes(contents).unwrap(), Arcade::default());
     game.init_code(2);
-    let mut status = Status::Running;
-    let mut score = 0;
-    let mut paddle_x = 0;
-    let mut ball_x = 0;
-    while status != Status::End {
-        status = game.process();
-        let output = game.read();
-        output.chunks(3).for_each(|c| {
-            if c[0] == -1 && c[1] == 0 { score = c[2] }
-            else if c[2] == 3 { paddle_x = c[0]; }
-            else if c[2] == 4 { ball_x = c[0]; }
-        });
-        let joystick = (ball_x - paddle_x).signum();
-        game.write_one(joystick);
+    game
+}
+
+// Follows the ball with the paddle, the joystick is read whenever the game needs it
+#[derive(Default)]
+struct Arcade {
+    tile: Vec<i64>,
+    score: i64,
+    paddle_x: i64,
+    ball_x: i64
+}
+
+impl Device for Arcade {
+    fn input(&mut self) -> Option<i64> {
+        Some((self.ball_x - self.paddle_x).signum())
     }
 
-    println!("day 13.2 - final score after the last block is broken: {}", score);
+    fn output(&mut self, value: i64) {
+        self.tile.push(value);
+        if let [x, y, id] = self.tile[..] {
+            if x == -1 && y == 0 { self.score = id }
+            else if id == 3 { self.paddle_x = x; }
+            else if id == 4 { self.ball_x = x; }
+            self.tile.clear();
+        }
+    }
 }
@@ -1,5 +1,5 @@
-use std::fs;
 use std::collections::HashMap;
+use crate::Answer;
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 struct Chemical<'a> {
@@ -13,20 +13,18 @@ struct Reaction<'a> {
     output: Chemical<'a>
 }
 
-pub fn first_star() {
-    let contents = fs::read_to_string("./input/day14.txt")
-        .expect("Something went wrong reading the file");
-
-    let ore = impl_first_star(&contents, "ORE","FUEL", 1);
-    println!("day 14.1 - minimum amount of ORE required to produce exactly 1 FUEL: {}", ore);
+pub fn first_star(contents: &str) -> Answer {
+    let ore = impl_first_star(contents, "ORE","FUEL", 1);
+    Answer::from(ore)
 }
 
-pub fn second_star() {
-    let contents = fs::read_to_string("./input/day14.txt")
-        .expect("Something went wrong reading the file");
+pub fn second_star(contents: &str) -> Answer {
+    let fuel = impl_second_star(contents, "ORE","FUEL", 1_000_000_000_000);
+    Answer::from(fuel)
+}
 
-    let fuel = impl_second_star(&contents, "ORE","FUEL", 1_000_000_000_000);
-    println!("day 14.2 - amount of FUEL produced with 1 trillion of ORE: {}", fuel);
+pub fn parse(contents: &str) {
+    std::hint::black_box(extract_reactions(contents));
 }
 
 fn impl_first_star(contents: &str, start: &str, end: &str, quantity: u64) -> u64 {
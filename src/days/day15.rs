@@ -1,5 +1,6 @@
-use std::fs;
+use crate::Answer;
 use crate::intcode::{ IntCode, extract_codes };
+use crate::session::Session;
 
 #[derive(PartialEq)]
 enum Command {
@@ -87,31 +88,37 @@ impl<'a> Step<'a> {
     }
 }
 
-pub fn first_star() {
-    let contents = fs::read_to_string("./input/day15.txt")
-        .expect("Something went wrong reading the file");
-
+pub fn first_star(contents: &str) -> Answer {
     let mut steps = vec![Step::new(Command::start(), (0, 0))];
-    let mut droid = IntCode::new(extract_codes(&contents));
+    let mut droid = IntCode::new(extract_codes(contents).unwrap());
     let steps_to_oxygen = run(&mut droid, &mut steps);
 
-    println!("day 15.1 - fewest number of movement commands: {}", steps_to_oxygen);
+    Answer::from(steps_to_oxygen)
+}
+
+pub fn second_star(contents: &str) -> Answer {
+    let mut droid = IntCode::new(extract_codes(contents).unwrap());
+    let max_path = fill(&mut droid);
+
+    Answer::from(max_path)
 }
 
-pub fn second_star() {
-    let contents = fs::read_to_string("./input/day15.txt")
-        .expect("Something went wrong reading the file");
+// Session of the droid exploring for both stars, to replay it
+pub fn session(contents: &str) -> Session {
+    let mut droid = IntCode::new(extract_codes(contents).unwrap());
+    droid.set_session(Session::default());
+    fill(&mut droid);
+    droid.take_session().unwrap()
+}
 
+fn fill(droid: &mut IntCode) -> usize {
     // first find oxygen system (first star)
     let mut steps = vec![Step::new(Command::start(), (0, 0))];
-    let mut droid = IntCode::new(extract_codes(&contents));
-    run(&mut droid, &mut steps);
+    run(droid, &mut steps);
     // then from oxygen system find longest path
     let oxygen_position = steps.last().unwrap().position;
     let mut steps = vec![Step::new(Command::start(), oxygen_position)];
-    let max_path = run(&mut droid, &mut steps);
-
-    println!("day 15.2 - minutes to fill with oxygen: {}", max_path);
+    run(droid, &mut steps)
 }
 
 fn run(droid: &mut IntCode, steps: &mut Vec<Step>) -> usize {
@@ -144,7 +151,7 @@ fn run(droid: &mut IntCode, steps: &mut Vec<Step>) -> usize {
 
     fn go(droid: &mut IntCode, command: &Command) -> Status {
         droid.write_one(command.to());
-        droid.process();
+        droid.process().unwrap();
         Status::from(droid.read_one().unwrap())
     }
 
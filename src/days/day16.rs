@@ -1,22 +1,18 @@
-use std::fs;
 use std::iter::repeat;
+use crate::Answer;
 
-pub fn first_star() {
-    let contents = fs::read_to_string("./input/day16.txt")
-        .expect("Something went wrong reading the file");
-
-    let digits = impl_first_star(extract_digits(&contents), 100);
-    println!("day 16.1 - first eight digits in the final output list: {}",
-             digits.iter().fold(String::new(), |a, d| format!("{}{}", a, d)));
+pub fn first_star(contents: &str) -> Answer {
+    let digits = impl_first_star(extract_digits(contents), 100);
+    Answer::from(digits.iter().fold(String::new(), |a, d| format!("{}{}", a, d)))
 }
 
-pub fn second_star() {
-    let contents = fs::read_to_string("./input/day16.txt")
-        .expect("Something went wrong reading the file");
+pub fn second_star(contents: &str) -> Answer {
+    let digits = impl_second_star(extract_digits(contents), 10_000, 100);
+    Answer::from(digits.iter().fold(String::new(), |a, d| format!("{}{}", a, d)))
+}
 
-    let digits = impl_second_star(extract_digits(&contents), 10_000, 100);
-    println!("day 16.2 - eight-digit message embedded in the final output list: {}",
-             digits.iter().fold(String::new(), |a, d| format!("{}{}", a, d)));
+pub fn parse(contents: &str) {
+    std::hint::black_box(extract_digits(contents));
 }
 
 fn impl_first_star(input: Vec<i8>, phases: usize) -> Vec<i8> {
@@ -1,4 +1,4 @@
-use std::fs;
+use crate::Answer;
 use crate::intcode::{ IntCode, extract_codes };
 
 #[derive(Clone, PartialEq)]
@@ -89,21 +89,15 @@ impl Command {
     }
 }
 
-pub fn first_star() {
-    let contents = fs::read_to_string("./input/day17.txt")
-        .expect("Something went wrong reading the file");
-
-    let grid = extract_grid(&extract_codes(&contents));
+pub fn first_star(contents: &str) -> Answer {
+    let grid = extract_grid(&extract_codes(contents).unwrap());
     let value = impl_first_star(&grid);
 
-    println!("day 17.1 - sum of alignment parameters: {}", value);
+    Answer::from(value)
 }
 
-pub fn second_star() {
-    let contents = fs::read_to_string("./input/day17.txt")
-        .expect("Something went wrong reading the file");
-
-    let codes = extract_codes(&contents);
+pub fn second_star(contents: &str) -> Answer {
+    let codes = extract_codes(contents).unwrap();
     let grid = extract_grid(&codes);
     let sequence = impl_second_star(&grid);
     let input = format!("{}\nn\n", sequence);
@@ -111,10 +105,10 @@ pub fn second_star() {
     let mut ascii = IntCode::new(codes);
     ascii.init_code(2);
     ascii.write_string(&input);
-    ascii.process();
+    ascii.process().unwrap();
     let value = ascii.read().pop().unwrap();
 
-    println!("day 17.2 - dust collected by vacuum robot: {}", value);
+    Answer::from(value)
 }
 
 fn impl_first_star(grid: &[Vec<char>])-> usize {
@@ -145,7 +139,7 @@ fn impl_second_star(grid: &[Vec<char>])-> String {
 
 fn extract_grid(codes: &[i64]) -> Vec<Vec<char>>{
     let mut ascii = IntCode::new(codes.to_vec());
-    ascii.process();
+    ascii.process().unwrap();
 
     let output = ascii.read_string();
 
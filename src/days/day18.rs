@@ -1,6 +1,6 @@
-use std::fs;
 use std::cmp::Reverse;
 use std::collections::{ BinaryHeap, BTreeMap, BTreeSet, VecDeque };
+use crate::Answer;
 
 type Position = (isize, isize);
 
@@ -85,23 +85,21 @@ impl Map {
     }
 }
 
-pub fn first_star() {
-    let contents = fs::read_to_string("./input/day18.txt")
-        .expect("Something went wrong reading the file");
+pub fn first_star(contents: &str) -> Answer {
+    let length = impl_first_star(contents);
 
-    let length = impl_first_star(&contents);
-
-    println!("day 18.1 - steps of shortest path that collects all of the keys: {}", length);
+    Answer::from(length)
 
 }
 
-pub fn second_star() {
-    let contents = fs::read_to_string("./input/day18.txt")
-        .expect("Something went wrong reading the file");
+pub fn second_star(contents: &str) -> Answer {
+    let length = impl_second_star(contents);
 
-    let length = impl_second_star(&contents);
+    Answer::from(length)
+}
 
-    println!("day 18.2 - fewest steps necessary to collect all of the keys: {}", length);
+pub fn parse(contents: &str) {
+    std::hint::black_box(extract_map(contents));
 }
 
 fn impl_first_star(contents: &str) -> usize {
@@ -1,11 +1,12 @@
-use std::fs;
+use crate::Answer;
 use crate::intcode::{ IntCode, extract_codes };
 
-pub fn first_star() {
-    let contents = fs::read_to_string("./input/day19.txt")
-        .expect("Something went wrong reading the file");
+// Translation of the input, generated with
+// cargo run --release compile input/day19.txt src/days/day19/beam.rs
+mod beam;
 
-    let codes = extract_codes(&contents);
+pub fn first_star(contents: &str) -> Answer {
+    let codes = extract_codes(contents).unwrap();
     let mut total = 0;
     for x in 0..50 {
         for y in 0..50 {
@@ -13,14 +14,11 @@ pub fn first_star() {
         }
     }
 
-    println!("day 19.1 - points affected by the tractor beam in the 50x50 area closest to the emitter: {}", total);
+    Answer::from(total)
 }
 
-pub fn second_star() {
-    let contents = fs::read_to_string("./input/day19.txt")
-        .expect("Something went wrong reading the file");
-
-    let codes = extract_codes(&contents);
+pub fn second_star(contents: &str) -> Answer {
+    let codes = extract_codes(contents).unwrap();
     let mut x = 0;
     let mut y = 0;
     let mut found = false;
@@ -44,12 +42,42 @@ pub fn second_star() {
         if !found { y += 1; }
     }
 
-    println!("day 19.2 - coordinates of point closest to the emitter of the 100x100 square (10000 * x + y): {}", 10_000 * x + y);
+    Answer::from(10_000 * x + y)
 }
 
 fn run_droid(x: i64, y: i64, codes: &[i64]) -> i64 {
     let mut droid = IntCode::new(codes.to_owned());
     droid.write(&[x, y]);
-    droid.process();
+    beam::compiled().process(&mut droid).unwrap();
     droid.read_one().unwrap()
-}
\ No newline at end of file
+}
+
+#[test]
+fn test0_beam() {
+    // the translation is regenerated along with the input
+    let codes = extract_codes(&std::fs::read_to_string("./input/day19.txt").unwrap()).unwrap();
+    assert_eq!(crate::compiler::translate(&codes), include_str!("day19/beam.rs"));
+}
+
+// cargo test --release bench_day19 -- --ignored --nocapture
+#[test]
+#[ignore]
+fn bench_day19() {
+    use std::time::Instant;
+
+    let codes = extract_codes(&std::fs::read_to_string("./input/day19.txt").unwrap()).unwrap();
+    let interpreted = |x, y| {
+        let mut droid = IntCode::new(codes.clone());
+        droid.write(&[x, y]);
+        droid.process().unwrap();
+        droid.read_one().unwrap()
+    };
+    let start = Instant::now();
+    let expected = (0..100).flat_map(|x| (0..100).map(move |y| (x, y))).map(|(x, y)| interpreted(x, y)).collect::<Vec<_>>();
+    let interpreter = start.elapsed();
+    let start = Instant::now();
+    let found = (0..100).flat_map(|x| (0..100).map(move |y| (x, y))).map(|(x, y)| run_droid(x, y, &codes)).collect::<Vec<_>>();
+    let compiled = start.elapsed();
+    assert_eq!(found, expected);
+    println!("100x100 points, interpreted: {:?}, compiled: {:?}", interpreter, compiled);
+}
@@ -0,0 +1,947 @@
+// Translated from an IntCode program of 424 words, do not edit
+use crate::compiler::{ self, Compiled };
+use crate::devices::Device;
+use crate::intcode::{ IntCode, Status };
+
+const CODES: &[i64] = &[
+    109, 424, 203, 1, 21102, 11, 1, 0, 1105, 1, 282, 21101, 18, 0, 0, 1106,
+    0, 259, 1202, 1, 1, 221, 203, 1, 21101, 0, 31, 0, 1106, 0, 282, 21102,
+    38, 1, 0, 1105, 1, 259, 20101, 0, 23, 2, 22102, 1, 1, 3, 21102, 1,
+    1, 1, 21102, 57, 1, 0, 1105, 1, 303, 2101, 0, 1, 222, 21002, 221, 1,
+    3, 20101, 0, 221, 2, 21102, 1, 259, 1, 21101, 0, 80, 0, 1105, 1, 225,
+    21102, 40, 1, 2, 21101, 0, 91, 0, 1105, 1, 303, 1201, 1, 0, 223, 20101,
+    0, 222, 4, 21101, 0, 259, 3, 21101, 0, 225, 2, 21101, 0, 225, 1, 21102,
+    118, 1, 0, 1105, 1, 225, 21001, 222, 0, 3, 21102, 1, 144, 2, 21101, 0,
+    133, 0, 1105, 1, 303, 21202, 1, -1, 1, 22001, 223, 1, 1, 21102, 148, 1,
+    0, 1105, 1, 259, 1202, 1, 1, 223, 20101, 0, 221, 4, 21001, 222, 0, 3,
+    21102, 1, 14, 2, 1001, 132, -2, 224, 1002, 224, 2, 224, 1001, 224, 3, 224,
+    1002, 132, -1, 132, 1, 224, 132, 224, 21001, 224, 1, 1, 21102, 195, 1, 0,
+    106, 0, 109, 20207, 1, 223, 2, 20101, 0, 23, 1, 21101, 0, -1, 3, 21102,
+    214, 1, 0, 1105, 1, 303, 22101, 1, 1, 1, 204, 1, 99, 0, 0, 0,
+    0, 109, 5, 2101, 0, -4, 249, 22101, 0, -3, 1, 21201, -2, 0, 2, 22101,
+    0, -1, 3, 21101, 0, 250, 0, 1105, 1, 225, 21202, 1, 1, -4, 109, -5,
+    2105, 1, 0, 109, 3, 22107, 0, -2, -1, 21202, -1, 2, -1, 21201, -1, -1,
+    -1, 22202, -1, -2, -2, 109, -3, 2106, 0, 0, 109, 3, 21207, -2, 0, -1,
+    1206, -1, 294, 104, 0, 99, 21202, -2, 1, -2, 109, -3, 2106, 0, 0, 109,
+    5, 22207, -3, -4, -1, 1206, -1, 346, 22201, -4, -3, -4, 21202, -3, -1, -1,
+    22201, -4, -1, 2, 21202, 2, -1, -1, 22201, -4, -1, 1, 21202, -2, 1, 3,
+    21101, 343, 0, 0, 1106, 0, 303, 1105, 1, 415, 22207, -2, -3, -1, 1206, -1,
+    387, 22201, -3, -2, -3, 21202, -2, -1, -1, 22201, -3, -1, 3, 21202, 3, -1,
+    -1, 22201, -3, -1, 2, 21202, -4, 1, 1, 21102, 384, 1, 0, 1106, 0, 303,
+    1105, 1, 415, 21202, -4, -1, -4, 22201, -4, -3, -4, 22202, -3, -2, -2, 22202,
+    -2, -4, -4, 22202, -3, -2, -3, 21202, -4, -1, -2, 22201, -3, -2, 1, 22101,
+    0, 1, -4, 109, -5, 2106, 0, 0
+];
+
+const CODE: &[bool] = &[
+    true, true, true, true, true, true, true, true, true, true, true, true, true, true, true, true,
+    true, true, true, true, true, true, true, true, true, true, true, true, true, true, true, true,
+    true, true, true, true, true, true, true, true, true, true, true, true, true, true, true, true,
+    true, true, true, true, true, true, true, true, true, true, true, true, true, true, true, true,
+    true, true, true, true, true, true, true, true, true, true, true, true, true, true, true, true,
+    true, true, true, true, true, true, true, true, true, true, true, true, true, true, true, true,
+    true, true, true, true, true, true, true, true, true, true, true, true, true, true, true, true,
+    true, true, true, true, true, true, true, true, true, true, true, true, true, true, true, true,
+    true, true, true, true, false, true, true, true, true, true, true, true, true, true, true, true,
+    true, true, true, true, true, true, true, true, true, true, true, true, true, true, true, true,
+    true, true, true, true, true, true, true, true, true, true, true, true, true, true, true, true,
+    true, true, true, true, true, true, true, true, true, true, true, true, true, true, true, true,
+    true, true, true, true, true, true, true, true, true, true, true, true, true, true, true, true,
+    true, true, true, true, true, true, true, true, true, true, true, true, true, false, false, false,
+    false, true, true, true, true, true, true, true, true, true, true, true, true, true, true, true,
+    true, true, true, true, true, true, true, true, true, false, true, true, true, true, true, true,
+    true, true, true, true, true, true, true, true, true, true, true, true, true, true, true, true,
+    true, true, true, true, true, true, true, true, true, true, true, true, true, true, true, true,
+    true, true, true, true, true, true, true, true, true, true, true, true, true, true, true, true,
+    true, true, true, true, true, true, true, true, true, true, true, true, true, true, true, true,
+    true, true, true, true, true, true, true, true, true, true, true, true, true, true, true, true,
+    true, true, true, true, true, true, true, true, true, true, true, true, true, true, true, true,
+    true, true, true, true, true, true, true, true, true, true, true, true, true, true, true, true,
+    true, true, true, true, true, true, true, true, true, true, true, true, true, true, true, true,
+    true, true, true, true, true, true, true, true, true, true, true, true, true, true, true, true,
+    true, true, true, true, true, true, true, true, true, true, true, true, true, true, true, true,
+    true, true, true, true, true, true, true, true
+];
+
+pub fn compiled<D: Device>() -> Compiled<D> {
+    Compiled { codes: CODES, code: CODE, execute }
+}
+
+#[allow(unused_variables, clippy::all)]
+fn execute<D: Device>(program: &mut IntCode<D>, pos: usize, base: &mut i64) -> Result<usize, Status> {
+    match pos {
+        // 0: ARB  #424
+        0 => {
+            let x: i64 = 424;
+            *base = base.checked_add(x).ok_or(Status::Running)?;
+            Ok(2)
+        },
+        // 2: IN   @1
+        2 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 1)?)?;
+            let input = program.device_mut().input().ok_or(Status::Waiting)?;
+            program.poke(address, input);
+            Ok(4)
+        },
+        // 4: MUL  #11, #1, @0
+        4 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 0)?)?;
+            let x: i64 = 11;
+            let y: i64 = 1;
+            program.poke(address, x.checked_mul(y).ok_or(Status::Running)?);
+            Ok(8)
+        },
+        // 8: JNZ  #1, #282
+        8 => {
+            let x: i64 = 1;
+            let y: i64 = 282;
+            if x != 0 { compiler::address(y) } else { Ok(11) }
+        },
+        // 11: ADD  #18, #0, @0
+        11 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 0)?)?;
+            let x: i64 = 18;
+            let y: i64 = 0;
+            program.poke(address, x.checked_add(y).ok_or(Status::Running)?);
+            Ok(15)
+        },
+        // 15: JZ   #0, #259
+        15 => {
+            let x: i64 = 0;
+            let y: i64 = 259;
+            if x == 0 { compiler::address(y) } else { Ok(18) }
+        },
+        // 18: MUL  @1, #1, 221
+        18 => {
+            let address = compiler::writable(CODE, 221)?;
+            let x: i64 = program.peek(compiler::relative(*base, 1)?);
+            let y: i64 = 1;
+            program.poke(address, x.checked_mul(y).ok_or(Status::Running)?);
+            Ok(22)
+        },
+        // 22: IN   @1
+        22 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 1)?)?;
+            let input = program.device_mut().input().ok_or(Status::Waiting)?;
+            program.poke(address, input);
+            Ok(24)
+        },
+        // 24: ADD  #0, #31, @0
+        24 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 0)?)?;
+            let x: i64 = 0;
+            let y: i64 = 31;
+            program.poke(address, x.checked_add(y).ok_or(Status::Running)?);
+            Ok(28)
+        },
+        // 28: JZ   #0, #282
+        28 => {
+            let x: i64 = 0;
+            let y: i64 = 282;
+            if x == 0 { compiler::address(y) } else { Ok(31) }
+        },
+        // 31: MUL  #38, #1, @0
+        31 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 0)?)?;
+            let x: i64 = 38;
+            let y: i64 = 1;
+            program.poke(address, x.checked_mul(y).ok_or(Status::Running)?);
+            Ok(35)
+        },
+        // 35: JNZ  #1, #259
+        35 => {
+            let x: i64 = 1;
+            let y: i64 = 259;
+            if x != 0 { compiler::address(y) } else { Ok(38) }
+        },
+        // 38: ADD  #0, 23, @2
+        38 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 2)?)?;
+            let x: i64 = 0;
+            let y: i64 = program.peek(23);
+            program.poke(address, x.checked_add(y).ok_or(Status::Running)?);
+            Ok(42)
+        },
+        // 42: MUL  #1, @1, @3
+        42 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 3)?)?;
+            let x: i64 = 1;
+            let y: i64 = program.peek(compiler::relative(*base, 1)?);
+            program.poke(address, x.checked_mul(y).ok_or(Status::Running)?);
+            Ok(46)
+        },
+        // 46: MUL  #1, #1, @1
+        46 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 1)?)?;
+            let x: i64 = 1;
+            let y: i64 = 1;
+            program.poke(address, x.checked_mul(y).ok_or(Status::Running)?);
+            Ok(50)
+        },
+        // 50: MUL  #57, #1, @0
+        50 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 0)?)?;
+            let x: i64 = 57;
+            let y: i64 = 1;
+            program.poke(address, x.checked_mul(y).ok_or(Status::Running)?);
+            Ok(54)
+        },
+        // 54: JNZ  #1, #303
+        54 => {
+            let x: i64 = 1;
+            let y: i64 = 303;
+            if x != 0 { compiler::address(y) } else { Ok(57) }
+        },
+        // 57: ADD  #0, @1, 222
+        57 => {
+            let address = compiler::writable(CODE, 222)?;
+            let x: i64 = 0;
+            let y: i64 = program.peek(compiler::relative(*base, 1)?);
+            program.poke(address, x.checked_add(y).ok_or(Status::Running)?);
+            Ok(61)
+        },
+        // 61: MUL  221, #1, @3
+        61 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 3)?)?;
+            let x: i64 = program.peek(221);
+            let y: i64 = 1;
+            program.poke(address, x.checked_mul(y).ok_or(Status::Running)?);
+            Ok(65)
+        },
+        // 65: ADD  #0, 221, @2
+        65 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 2)?)?;
+            let x: i64 = 0;
+            let y: i64 = program.peek(221);
+            program.poke(address, x.checked_add(y).ok_or(Status::Running)?);
+            Ok(69)
+        },
+        // 69: MUL  #1, #259, @1
+        69 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 1)?)?;
+            let x: i64 = 1;
+            let y: i64 = 259;
+            program.poke(address, x.checked_mul(y).ok_or(Status::Running)?);
+            Ok(73)
+        },
+        // 73: ADD  #0, #80, @0
+        73 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 0)?)?;
+            let x: i64 = 0;
+            let y: i64 = 80;
+            program.poke(address, x.checked_add(y).ok_or(Status::Running)?);
+            Ok(77)
+        },
+        // 77: JNZ  #1, #225
+        77 => {
+            let x: i64 = 1;
+            let y: i64 = 225;
+            if x != 0 { compiler::address(y) } else { Ok(80) }
+        },
+        // 80: MUL  #40, #1, @2
+        80 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 2)?)?;
+            let x: i64 = 40;
+            let y: i64 = 1;
+            program.poke(address, x.checked_mul(y).ok_or(Status::Running)?);
+            Ok(84)
+        },
+        // 84: ADD  #0, #91, @0
+        84 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 0)?)?;
+            let x: i64 = 0;
+            let y: i64 = 91;
+            program.poke(address, x.checked_add(y).ok_or(Status::Running)?);
+            Ok(88)
+        },
+        // 88: JNZ  #1, #303
+        88 => {
+            let x: i64 = 1;
+            let y: i64 = 303;
+            if x != 0 { compiler::address(y) } else { Ok(91) }
+        },
+        // 91: ADD  @1, #0, 223
+        91 => {
+            let address = compiler::writable(CODE, 223)?;
+            let x: i64 = program.peek(compiler::relative(*base, 1)?);
+            let y: i64 = 0;
+            program.poke(address, x.checked_add(y).ok_or(Status::Running)?);
+            Ok(95)
+        },
+        // 95: ADD  #0, 222, @4
+        95 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 4)?)?;
+            let x: i64 = 0;
+            let y: i64 = program.peek(222);
+            program.poke(address, x.checked_add(y).ok_or(Status::Running)?);
+            Ok(99)
+        },
+        // 99: ADD  #0, #259, @3
+        99 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 3)?)?;
+            let x: i64 = 0;
+            let y: i64 = 259;
+            program.poke(address, x.checked_add(y).ok_or(Status::Running)?);
+            Ok(103)
+        },
+        // 103: ADD  #0, #225, @2
+        103 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 2)?)?;
+            let x: i64 = 0;
+            let y: i64 = 225;
+            program.poke(address, x.checked_add(y).ok_or(Status::Running)?);
+            Ok(107)
+        },
+        // 107: ADD  #0, #225, @1
+        107 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 1)?)?;
+            let x: i64 = 0;
+            let y: i64 = 225;
+            program.poke(address, x.checked_add(y).ok_or(Status::Running)?);
+            Ok(111)
+        },
+        // 111: MUL  #118, #1, @0
+        111 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 0)?)?;
+            let x: i64 = 118;
+            let y: i64 = 1;
+            program.poke(address, x.checked_mul(y).ok_or(Status::Running)?);
+            Ok(115)
+        },
+        // 115: JNZ  #1, #225
+        115 => {
+            let x: i64 = 1;
+            let y: i64 = 225;
+            if x != 0 { compiler::address(y) } else { Ok(118) }
+        },
+        // 118: ADD  222, #0, @3
+        118 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 3)?)?;
+            let x: i64 = program.peek(222);
+            let y: i64 = 0;
+            program.poke(address, x.checked_add(y).ok_or(Status::Running)?);
+            Ok(122)
+        },
+        // 122: MUL  #1, #144, @2
+        122 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 2)?)?;
+            let x: i64 = 1;
+            let y: i64 = 144;
+            program.poke(address, x.checked_mul(y).ok_or(Status::Running)?);
+            Ok(126)
+        },
+        // 126: ADD  #0, #133, @0
+        126 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 0)?)?;
+            let x: i64 = 0;
+            let y: i64 = 133;
+            program.poke(address, x.checked_add(y).ok_or(Status::Running)?);
+            Ok(130)
+        },
+        // 130: JNZ  #1, #303
+        130 => {
+            let x: i64 = 1;
+            let y: i64 = program.peek(132);
+            if x != 0 { compiler::address(y) } else { Ok(133) }
+        },
+        // 133: MUL  @1, #-1, @1
+        133 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 1)?)?;
+            let x: i64 = program.peek(compiler::relative(*base, 1)?);
+            let y: i64 = -1;
+            program.poke(address, x.checked_mul(y).ok_or(Status::Running)?);
+            Ok(137)
+        },
+        // 137: ADD  223, @1, @1
+        137 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 1)?)?;
+            let x: i64 = program.peek(223);
+            let y: i64 = program.peek(compiler::relative(*base, 1)?);
+            program.poke(address, x.checked_add(y).ok_or(Status::Running)?);
+            Ok(141)
+        },
+        // 141: MUL  #148, #1, @0
+        141 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 0)?)?;
+            let x: i64 = 148;
+            let y: i64 = 1;
+            program.poke(address, x.checked_mul(y).ok_or(Status::Running)?);
+            Ok(145)
+        },
+        // 145: JNZ  #1, #259
+        145 => {
+            let x: i64 = 1;
+            let y: i64 = 259;
+            if x != 0 { compiler::address(y) } else { Ok(148) }
+        },
+        // 148: MUL  @1, #1, 223
+        148 => {
+            let address = compiler::writable(CODE, 223)?;
+            let x: i64 = program.peek(compiler::relative(*base, 1)?);
+            let y: i64 = 1;
+            program.poke(address, x.checked_mul(y).ok_or(Status::Running)?);
+            Ok(152)
+        },
+        // 152: ADD  #0, 221, @4
+        152 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 4)?)?;
+            let x: i64 = 0;
+            let y: i64 = program.peek(221);
+            program.poke(address, x.checked_add(y).ok_or(Status::Running)?);
+            Ok(156)
+        },
+        // 156: ADD  222, #0, @3
+        156 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 3)?)?;
+            let x: i64 = program.peek(222);
+            let y: i64 = 0;
+            program.poke(address, x.checked_add(y).ok_or(Status::Running)?);
+            Ok(160)
+        },
+        // 160: MUL  #1, #14, @2
+        160 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 2)?)?;
+            let x: i64 = 1;
+            let y: i64 = 14;
+            program.poke(address, x.checked_mul(y).ok_or(Status::Running)?);
+            Ok(164)
+        },
+        // 164: ADD  132, #-2, 224
+        164 => {
+            let address = compiler::writable(CODE, 224)?;
+            let x: i64 = program.peek(132);
+            let y: i64 = -2;
+            program.poke(address, x.checked_add(y).ok_or(Status::Running)?);
+            Ok(168)
+        },
+        // 168: MUL  224, #2, 224
+        168 => {
+            let address = compiler::writable(CODE, 224)?;
+            let x: i64 = program.peek(224);
+            let y: i64 = 2;
+            program.poke(address, x.checked_mul(y).ok_or(Status::Running)?);
+            Ok(172)
+        },
+        // 172: ADD  224, #3, 224
+        172 => {
+            let address = compiler::writable(CODE, 224)?;
+            let x: i64 = program.peek(224);
+            let y: i64 = 3;
+            program.poke(address, x.checked_add(y).ok_or(Status::Running)?);
+            Ok(176)
+        },
+        // 176: MUL  132, #-1, 132
+        176 => {
+            let address = compiler::writable(CODE, 132)?;
+            let x: i64 = program.peek(132);
+            let y: i64 = -1;
+            program.poke(address, x.checked_mul(y).ok_or(Status::Running)?);
+            Ok(180)
+        },
+        // 180: ADD  224, 132, 224
+        180 => {
+            let address = compiler::writable(CODE, 224)?;
+            let x: i64 = program.peek(224);
+            let y: i64 = program.peek(132);
+            program.poke(address, x.checked_add(y).ok_or(Status::Running)?);
+            Ok(184)
+        },
+        // 184: ADD  224, #1, @1
+        184 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 1)?)?;
+            let x: i64 = program.peek(224);
+            let y: i64 = 1;
+            program.poke(address, x.checked_add(y).ok_or(Status::Running)?);
+            Ok(188)
+        },
+        // 188: MUL  #195, #1, @0
+        188 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 0)?)?;
+            let x: i64 = 195;
+            let y: i64 = 1;
+            program.poke(address, x.checked_mul(y).ok_or(Status::Running)?);
+            Ok(192)
+        },
+        // 192: JZ   #0, 109
+        192 => {
+            let x: i64 = 0;
+            let y: i64 = program.peek(109);
+            if x == 0 { compiler::address(y) } else { Ok(195) }
+        },
+        // 195: LT   @1, 223, @2
+        195 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 2)?)?;
+            let x: i64 = program.peek(compiler::relative(*base, 1)?);
+            let y: i64 = program.peek(223);
+            program.poke(address, (x < y) as i64);
+            Ok(199)
+        },
+        // 199: ADD  #0, 23, @1
+        199 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 1)?)?;
+            let x: i64 = 0;
+            let y: i64 = program.peek(23);
+            program.poke(address, x.checked_add(y).ok_or(Status::Running)?);
+            Ok(203)
+        },
+        // 203: ADD  #0, #-1, @3
+        203 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 3)?)?;
+            let x: i64 = 0;
+            let y: i64 = -1;
+            program.poke(address, x.checked_add(y).ok_or(Status::Running)?);
+            Ok(207)
+        },
+        // 207: MUL  #214, #1, @0
+        207 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 0)?)?;
+            let x: i64 = 214;
+            let y: i64 = 1;
+            program.poke(address, x.checked_mul(y).ok_or(Status::Running)?);
+            Ok(211)
+        },
+        // 211: JNZ  #1, #303
+        211 => {
+            let x: i64 = 1;
+            let y: i64 = 303;
+            if x != 0 { compiler::address(y) } else { Ok(214) }
+        },
+        // 214: ADD  #1, @1, @1
+        214 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 1)?)?;
+            let x: i64 = 1;
+            let y: i64 = program.peek(compiler::relative(*base, 1)?);
+            program.poke(address, x.checked_add(y).ok_or(Status::Running)?);
+            Ok(218)
+        },
+        // 218: OUT  @1
+        218 => {
+            let x: i64 = program.peek(compiler::relative(*base, 1)?);
+            program.device_mut().output(x);
+            Ok(220)
+        },
+        // 220: HLT
+        220 => {
+            Err(Status::End)
+        },
+        // 225: ARB  #5
+        225 => {
+            let x: i64 = 5;
+            *base = base.checked_add(x).ok_or(Status::Running)?;
+            Ok(227)
+        },
+        // 227: ADD  #0, @-4, 249
+        227 => {
+            let address = compiler::writable(CODE, 249)?;
+            let x: i64 = 0;
+            let y: i64 = program.peek(compiler::relative(*base, -4)?);
+            program.poke(address, x.checked_add(y).ok_or(Status::Running)?);
+            Ok(231)
+        },
+        // 231: ADD  #0, @-3, @1
+        231 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 1)?)?;
+            let x: i64 = 0;
+            let y: i64 = program.peek(compiler::relative(*base, -3)?);
+            program.poke(address, x.checked_add(y).ok_or(Status::Running)?);
+            Ok(235)
+        },
+        // 235: ADD  @-2, #0, @2
+        235 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 2)?)?;
+            let x: i64 = program.peek(compiler::relative(*base, -2)?);
+            let y: i64 = 0;
+            program.poke(address, x.checked_add(y).ok_or(Status::Running)?);
+            Ok(239)
+        },
+        // 239: ADD  #0, @-1, @3
+        239 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 3)?)?;
+            let x: i64 = 0;
+            let y: i64 = program.peek(compiler::relative(*base, -1)?);
+            program.poke(address, x.checked_add(y).ok_or(Status::Running)?);
+            Ok(243)
+        },
+        // 243: ADD  #0, #250, @0
+        243 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 0)?)?;
+            let x: i64 = 0;
+            let y: i64 = 250;
+            program.poke(address, x.checked_add(y).ok_or(Status::Running)?);
+            Ok(247)
+        },
+        // 247: JNZ  #1, #225
+        247 => {
+            let x: i64 = 1;
+            let y: i64 = program.peek(249);
+            if x != 0 { compiler::address(y) } else { Ok(250) }
+        },
+        // 250: MUL  @1, #1, @-4
+        250 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, -4)?)?;
+            let x: i64 = program.peek(compiler::relative(*base, 1)?);
+            let y: i64 = 1;
+            program.poke(address, x.checked_mul(y).ok_or(Status::Running)?);
+            Ok(254)
+        },
+        // 254: ARB  #-5
+        254 => {
+            let x: i64 = -5;
+            *base = base.checked_add(x).ok_or(Status::Running)?;
+            Ok(256)
+        },
+        // 256: JNZ  #1, @0
+        256 => {
+            let x: i64 = 1;
+            let y: i64 = program.peek(compiler::relative(*base, 0)?);
+            if x != 0 { compiler::address(y) } else { Ok(259) }
+        },
+        // 259: ARB  #3
+        259 => {
+            let x: i64 = 3;
+            *base = base.checked_add(x).ok_or(Status::Running)?;
+            Ok(261)
+        },
+        // 261: LT   #0, @-2, @-1
+        261 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, -1)?)?;
+            let x: i64 = 0;
+            let y: i64 = program.peek(compiler::relative(*base, -2)?);
+            program.poke(address, (x < y) as i64);
+            Ok(265)
+        },
+        // 265: MUL  @-1, #2, @-1
+        265 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, -1)?)?;
+            let x: i64 = program.peek(compiler::relative(*base, -1)?);
+            let y: i64 = 2;
+            program.poke(address, x.checked_mul(y).ok_or(Status::Running)?);
+            Ok(269)
+        },
+        // 269: ADD  @-1, #-1, @-1
+        269 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, -1)?)?;
+            let x: i64 = program.peek(compiler::relative(*base, -1)?);
+            let y: i64 = -1;
+            program.poke(address, x.checked_add(y).ok_or(Status::Running)?);
+            Ok(273)
+        },
+        // 273: MUL  @-1, @-2, @-2
+        273 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, -2)?)?;
+            let x: i64 = program.peek(compiler::relative(*base, -1)?);
+            let y: i64 = program.peek(compiler::relative(*base, -2)?);
+            program.poke(address, x.checked_mul(y).ok_or(Status::Running)?);
+            Ok(277)
+        },
+        // 277: ARB  #-3
+        277 => {
+            let x: i64 = -3;
+            *base = base.checked_add(x).ok_or(Status::Running)?;
+            Ok(279)
+        },
+        // 279: JZ   #0, @0
+        279 => {
+            let x: i64 = 0;
+            let y: i64 = program.peek(compiler::relative(*base, 0)?);
+            if x == 0 { compiler::address(y) } else { Ok(282) }
+        },
+        // 282: ARB  #3
+        282 => {
+            let x: i64 = 3;
+            *base = base.checked_add(x).ok_or(Status::Running)?;
+            Ok(284)
+        },
+        // 284: LT   @-2, #0, @-1
+        284 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, -1)?)?;
+            let x: i64 = program.peek(compiler::relative(*base, -2)?);
+            let y: i64 = 0;
+            program.poke(address, (x < y) as i64);
+            Ok(288)
+        },
+        // 288: JZ   @-1, #294
+        288 => {
+            let x: i64 = program.peek(compiler::relative(*base, -1)?);
+            let y: i64 = 294;
+            if x == 0 { compiler::address(y) } else { Ok(291) }
+        },
+        // 291: OUT  #0
+        291 => {
+            let x: i64 = 0;
+            program.device_mut().output(x);
+            Ok(293)
+        },
+        // 293: HLT
+        293 => {
+            Err(Status::End)
+        },
+        // 294: MUL  @-2, #1, @-2
+        294 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, -2)?)?;
+            let x: i64 = program.peek(compiler::relative(*base, -2)?);
+            let y: i64 = 1;
+            program.poke(address, x.checked_mul(y).ok_or(Status::Running)?);
+            Ok(298)
+        },
+        // 298: ARB  #-3
+        298 => {
+            let x: i64 = -3;
+            *base = base.checked_add(x).ok_or(Status::Running)?;
+            Ok(300)
+        },
+        // 300: JZ   #0, @0
+        300 => {
+            let x: i64 = 0;
+            let y: i64 = program.peek(compiler::relative(*base, 0)?);
+            if x == 0 { compiler::address(y) } else { Ok(303) }
+        },
+        // 303: ARB  #5
+        303 => {
+            let x: i64 = 5;
+            *base = base.checked_add(x).ok_or(Status::Running)?;
+            Ok(305)
+        },
+        // 305: LT   @-3, @-4, @-1
+        305 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, -1)?)?;
+            let x: i64 = program.peek(compiler::relative(*base, -3)?);
+            let y: i64 = program.peek(compiler::relative(*base, -4)?);
+            program.poke(address, (x < y) as i64);
+            Ok(309)
+        },
+        // 309: JZ   @-1, #346
+        309 => {
+            let x: i64 = program.peek(compiler::relative(*base, -1)?);
+            let y: i64 = 346;
+            if x == 0 { compiler::address(y) } else { Ok(312) }
+        },
+        // 312: ADD  @-4, @-3, @-4
+        312 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, -4)?)?;
+            let x: i64 = program.peek(compiler::relative(*base, -4)?);
+            let y: i64 = program.peek(compiler::relative(*base, -3)?);
+            program.poke(address, x.checked_add(y).ok_or(Status::Running)?);
+            Ok(316)
+        },
+        // 316: MUL  @-3, #-1, @-1
+        316 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, -1)?)?;
+            let x: i64 = program.peek(compiler::relative(*base, -3)?);
+            let y: i64 = -1;
+            program.poke(address, x.checked_mul(y).ok_or(Status::Running)?);
+            Ok(320)
+        },
+        // 320: ADD  @-4, @-1, @2
+        320 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 2)?)?;
+            let x: i64 = program.peek(compiler::relative(*base, -4)?);
+            let y: i64 = program.peek(compiler::relative(*base, -1)?);
+            program.poke(address, x.checked_add(y).ok_or(Status::Running)?);
+            Ok(324)
+        },
+        // 324: MUL  @2, #-1, @-1
+        324 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, -1)?)?;
+            let x: i64 = program.peek(compiler::relative(*base, 2)?);
+            let y: i64 = -1;
+            program.poke(address, x.checked_mul(y).ok_or(Status::Running)?);
+            Ok(328)
+        },
+        // 328: ADD  @-4, @-1, @1
+        328 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 1)?)?;
+            let x: i64 = program.peek(compiler::relative(*base, -4)?);
+            let y: i64 = program.peek(compiler::relative(*base, -1)?);
+            program.poke(address, x.checked_add(y).ok_or(Status::Running)?);
+            Ok(332)
+        },
+        // 332: MUL  @-2, #1, @3
+        332 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 3)?)?;
+            let x: i64 = program.peek(compiler::relative(*base, -2)?);
+            let y: i64 = 1;
+            program.poke(address, x.checked_mul(y).ok_or(Status::Running)?);
+            Ok(336)
+        },
+        // 336: ADD  #343, #0, @0
+        336 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 0)?)?;
+            let x: i64 = 343;
+            let y: i64 = 0;
+            program.poke(address, x.checked_add(y).ok_or(Status::Running)?);
+            Ok(340)
+        },
+        // 340: JZ   #0, #303
+        340 => {
+            let x: i64 = 0;
+            let y: i64 = 303;
+            if x == 0 { compiler::address(y) } else { Ok(343) }
+        },
+        // 343: JNZ  #1, #415
+        343 => {
+            let x: i64 = 1;
+            let y: i64 = 415;
+            if x != 0 { compiler::address(y) } else { Ok(346) }
+        },
+        // 346: LT   @-2, @-3, @-1
+        346 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, -1)?)?;
+            let x: i64 = program.peek(compiler::relative(*base, -2)?);
+            let y: i64 = program.peek(compiler::relative(*base, -3)?);
+            program.poke(address, (x < y) as i64);
+            Ok(350)
+        },
+        // 350: JZ   @-1, #387
+        350 => {
+            let x: i64 = program.peek(compiler::relative(*base, -1)?);
+            let y: i64 = 387;
+            if x == 0 { compiler::address(y) } else { Ok(353) }
+        },
+        // 353: ADD  @-3, @-2, @-3
+        353 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, -3)?)?;
+            let x: i64 = program.peek(compiler::relative(*base, -3)?);
+            let y: i64 = program.peek(compiler::relative(*base, -2)?);
+            program.poke(address, x.checked_add(y).ok_or(Status::Running)?);
+            Ok(357)
+        },
+        // 357: MUL  @-2, #-1, @-1
+        357 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, -1)?)?;
+            let x: i64 = program.peek(compiler::relative(*base, -2)?);
+            let y: i64 = -1;
+            program.poke(address, x.checked_mul(y).ok_or(Status::Running)?);
+            Ok(361)
+        },
+        // 361: ADD  @-3, @-1, @3
+        361 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 3)?)?;
+            let x: i64 = program.peek(compiler::relative(*base, -3)?);
+            let y: i64 = program.peek(compiler::relative(*base, -1)?);
+            program.poke(address, x.checked_add(y).ok_or(Status::Running)?);
+            Ok(365)
+        },
+        // 365: MUL  @3, #-1, @-1
+        365 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, -1)?)?;
+            let x: i64 = program.peek(compiler::relative(*base, 3)?);
+            let y: i64 = -1;
+            program.poke(address, x.checked_mul(y).ok_or(Status::Running)?);
+            Ok(369)
+        },
+        // 369: ADD  @-3, @-1, @2
+        369 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 2)?)?;
+            let x: i64 = program.peek(compiler::relative(*base, -3)?);
+            let y: i64 = program.peek(compiler::relative(*base, -1)?);
+            program.poke(address, x.checked_add(y).ok_or(Status::Running)?);
+            Ok(373)
+        },
+        // 373: MUL  @-4, #1, @1
+        373 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 1)?)?;
+            let x: i64 = program.peek(compiler::relative(*base, -4)?);
+            let y: i64 = 1;
+            program.poke(address, x.checked_mul(y).ok_or(Status::Running)?);
+            Ok(377)
+        },
+        // 377: MUL  #384, #1, @0
+        377 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 0)?)?;
+            let x: i64 = 384;
+            let y: i64 = 1;
+            program.poke(address, x.checked_mul(y).ok_or(Status::Running)?);
+            Ok(381)
+        },
+        // 381: JZ   #0, #303
+        381 => {
+            let x: i64 = 0;
+            let y: i64 = 303;
+            if x == 0 { compiler::address(y) } else { Ok(384) }
+        },
+        // 384: JNZ  #1, #415
+        384 => {
+            let x: i64 = 1;
+            let y: i64 = 415;
+            if x != 0 { compiler::address(y) } else { Ok(387) }
+        },
+        // 387: MUL  @-4, #-1, @-4
+        387 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, -4)?)?;
+            let x: i64 = program.peek(compiler::relative(*base, -4)?);
+            let y: i64 = -1;
+            program.poke(address, x.checked_mul(y).ok_or(Status::Running)?);
+            Ok(391)
+        },
+        // 391: ADD  @-4, @-3, @-4
+        391 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, -4)?)?;
+            let x: i64 = program.peek(compiler::relative(*base, -4)?);
+            let y: i64 = program.peek(compiler::relative(*base, -3)?);
+            program.poke(address, x.checked_add(y).ok_or(Status::Running)?);
+            Ok(395)
+        },
+        // 395: MUL  @-3, @-2, @-2
+        395 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, -2)?)?;
+            let x: i64 = program.peek(compiler::relative(*base, -3)?);
+            let y: i64 = program.peek(compiler::relative(*base, -2)?);
+            program.poke(address, x.checked_mul(y).ok_or(Status::Running)?);
+            Ok(399)
+        },
+        // 399: MUL  @-2, @-4, @-4
+        399 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, -4)?)?;
+            let x: i64 = program.peek(compiler::relative(*base, -2)?);
+            let y: i64 = program.peek(compiler::relative(*base, -4)?);
+            program.poke(address, x.checked_mul(y).ok_or(Status::Running)?);
+            Ok(403)
+        },
+        // 403: MUL  @-3, @-2, @-3
+        403 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, -3)?)?;
+            let x: i64 = program.peek(compiler::relative(*base, -3)?);
+            let y: i64 = program.peek(compiler::relative(*base, -2)?);
+            program.poke(address, x.checked_mul(y).ok_or(Status::Running)?);
+            Ok(407)
+        },
+        // 407: MUL  @-4, #-1, @-2
+        407 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, -2)?)?;
+            let x: i64 = program.peek(compiler::relative(*base, -4)?);
+            let y: i64 = -1;
+            program.poke(address, x.checked_mul(y).ok_or(Status::Running)?);
+            Ok(411)
+        },
+        // 411: ADD  @-3, @-2, @1
+        411 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, 1)?)?;
+            let x: i64 = program.peek(compiler::relative(*base, -3)?);
+            let y: i64 = program.peek(compiler::relative(*base, -2)?);
+            program.poke(address, x.checked_add(y).ok_or(Status::Running)?);
+            Ok(415)
+        },
+        // 415: ADD  #0, @1, @-4
+        415 => {
+            let address = compiler::writable(CODE, compiler::relative(*base, -4)?)?;
+            let x: i64 = 0;
+            let y: i64 = program.peek(compiler::relative(*base, 1)?);
+            program.poke(address, x.checked_add(y).ok_or(Status::Running)?);
+            Ok(419)
+        },
+        // 419: ARB  #-5
+        419 => {
+            let x: i64 = -5;
+            *base = base.checked_add(x).ok_or(Status::Running)?;
+            Ok(421)
+        },
+        // 421: JZ   #0, @0
+        421 => {
+            let x: i64 = 0;
+            let y: i64 = program.peek(compiler::relative(*base, 0)?);
+            if x == 0 { compiler::address(y) } else { Ok(424) }
+        },
+        _ => Err(Status::Running)
+    }
+}
@@ -1,5 +1,5 @@
-use std::fs;
 use std::collections::{ BTreeMap, BTreeSet, VecDeque };
+use crate::Answer;
 
 type Position = (isize, isize);
 
@@ -7,22 +7,20 @@ type Portal = (Position, String, bool);
 
 type Paths<'a> = BTreeMap<&'a Portal, BTreeMap<&'a Portal, usize>>;
 
-pub fn first_star() {
-    let contents = fs::read_to_string("./input/day20.txt")
-        .expect("Something went wrong reading the file");
+pub fn first_star(contents: &str) -> Answer {
+    let length = impl_star(true, contents);
 
-    let length = impl_star(true, &contents);
-
-    println!("day 20.1 - steps to get from the open tile marked AA to the open tile marked ZZ: {}", length);
+    Answer::from(length)
 }
 
-pub fn second_star() {
-    let contents = fs::read_to_string("./input/day20.txt")
-        .expect("Something went wrong reading the file");
+pub fn second_star(contents: &str) -> Answer {
+    let length = impl_star(false, contents);
 
-    let length = impl_star(false, &contents);
+    Answer::from(length)
+}
 
-    println!("day 20.2 - steps to get from the open tile marked AA to the open tile marked ZZ, both at the outermost layer: {}", length);
+pub fn parse(contents: &str) {
+    std::hint::black_box(extract_map(contents));
 }
 
 fn impl_star(first: bool, contents: &str) -> usize {
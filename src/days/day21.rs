@@ -1,12 +1,9 @@
-use std::fs;
+use crate::Answer;
+use crate::devices::{ AsciiInput, AsciiOutput };
 use crate::intcode::{ IntCode, extract_codes };
 
-pub fn first_star() {
-    let contents = fs::read_to_string("./input/day21.txt")
-        .expect("Something went wrong reading the file");
-
-    let codes = extract_codes(&contents);
-    let mut jump_droid = IntCode::new(codes);
+pub fn first_star(contents: &str) -> Answer {
+    let codes = extract_codes(contents).unwrap();
     let instructions = "\
             OR A T\n\
             AND B T\n\
@@ -16,19 +13,14 @@ pub fn first_star() {
             WALK\n\
         ";
 
-    jump_droid.write_string(instructions);
-    jump_droid.process();
-    let output = jump_droid.read();
-    let result = output.last().unwrap();
-    println!("day 21.1 - amount of reported damage to the hull: {}", result);
+    let mut jump_droid = IntCode::with_device(codes, (AsciiInput::new(instructions), AsciiOutput::default()));
+    jump_droid.process().unwrap();
+    let result = jump_droid.device().1.numbers.last().unwrap();
+    Answer::from(*result)
 }
 
-pub fn second_star() {
-    let contents = fs::read_to_string("./input/day21.txt")
-        .expect("Something went wrong reading the file");
-
-    let codes = extract_codes(&contents);
-    let mut jump_droid = IntCode::new(codes);
+pub fn second_star(contents: &str) -> Answer {
+    let codes = extract_codes(contents).unwrap();
     let instructions = "\
             OR A T\n\
             AND B T\n\
@@ -41,9 +33,8 @@ pub fn second_star() {
             RUN\n\
         ";
 
-    jump_droid.write_string(instructions);
-    jump_droid.process();
-    let output = jump_droid.read();
-    let result = output.last().unwrap();
-    println!("day 21.2 - amount of reported damage to the hull: {}", result);
+    let mut jump_droid = IntCode::with_device(codes, (AsciiInput::new(instructions), AsciiOutput::default()));
+    jump_droid.process().unwrap();
+    let result = jump_droid.device().1.numbers.last().unwrap();
+    Answer::from(*result)
 }
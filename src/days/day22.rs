@@ -1,29 +1,23 @@
-use std::fs;
 use modinverse::modinverse;
+use crate::Answer;
 
 type Num = i128;
 type Coeff = (Num, Num);
 
-pub fn first_star() {
-    let contents = fs::read_to_string("./input/day22.txt")
-        .expect("Something went wrong reading the file");
-
+pub fn first_star(contents: &str) -> Answer {
     let size = 10_007;
     let iterations = 1;
-    let (position_of_card, _) = compose_shuffles(&contents, size, iterations);
+    let (position_of_card, _) = compose_shuffles(contents, size, iterations);
 
-    println!("day 22.1 - position of card 2019: {}", position_of_card(2019));
+    Answer::from(position_of_card(2019))
 }
 
-pub fn second_star() {
-    let contents = fs::read_to_string("./input/day22.txt")
-        .expect("Something went wrong reading the file");
-
+pub fn second_star(contents: &str) -> Answer {
     let size = 119_315_717_514_047;
     let iterations = 101_741_582_076_661;
-    let (_, card_at_position) = compose_shuffles(&contents, size, iterations);
+    let (_, card_at_position) = compose_shuffles(contents, size, iterations);
 
-    println!("day 22.2 - card at position 2020: {}", card_at_position(2020));
+    Answer::from(card_at_position(2020))
 }
 
 fn compose_shuffles(contents: &str, size: Num, mut iterations: Num) -> (impl Fn(Num) -> Num, impl Fn(Num) -> Num) {
@@ -1,99 +1,63 @@
-use std::fs;
-use crate::intcode::{ IntCode, extract_codes };
+use crate::Answer;
+use crate::intcode::extract_codes;
+use crate::network::{ Action, Nat, Network, Packet };
 
-#[derive(Default)]
-struct Packet {
-    address: usize,
-    x: i64,
-    y: i64
-}
-
-pub fn first_star() {
-    let contents = fs::read_to_string("./input/day23.txt")
-        .expect("Something went wrong reading the file");
-
-    let codes = extract_codes(&contents);
-
-    let size = 50;
-    let mut computers = (0..size).map(|i| init_computer(i, &codes)).collect::<Vec<_>>();
+pub fn first_star(contents: &str) -> Answer {
+    let codes = extract_codes(contents).unwrap();
 
-    let mut packet = Packet::default();
+    let mut nat = FirstPacket::default();
+    Network::new(codes, 50).run(&mut nat).unwrap();
 
-    while packet.address != 255 {
-        packet = network(&mut computers);
-
-        if packet.address != 255 {
-            computers.iter_mut().for_each(|c| {
-                if c.no_input() {
-                    c.write_one(-1);
-                }
-                c.process();
-            });
-        }
-    }
-
-    println!("day 23.1 - Y value of the first packet sent to address 255: {}", packet.y);
+    Answer::from(nat.y)
 }
 
-pub fn second_star() {
-    let contents = fs::read_to_string("./input/day23.txt")
-        .expect("Something went wrong reading the file");
-
-    let codes = extract_codes(&contents);
-
-    let size = 50;
-    let mut computers = (0..size).map(|i| init_computer(i, &codes)).collect::<Vec<_>>();
+pub fn second_star(contents: &str) -> Answer {
+    let codes = extract_codes(contents).unwrap();
 
-    let mut y = 0;
+    let mut nat = Monitor::default();
+    Network::new(codes, 50).run(&mut nat).unwrap();
+    let y = nat.delivered.unwrap();
 
-    loop {
-        let packet = network(&mut computers);
-
-        let idle = computers.iter_mut().all(|c| {
-            let no_input = c.no_input();
-            if no_input {
-                c.write_one(-1);
-            }
-            c.process();
-            no_input && c.no_output()
-        });
+    Answer::from(y)
+}
 
-        if idle && y == packet.y { break }
+#[derive(Default)]
+struct FirstPacket {
+    y: i64
+}
 
-        if idle {
-            computers[0].write(&[packet.x, packet.y]);
-            y = packet.y;
-        }
+impl Nat for FirstPacket {
+    fn receive(&mut self, packet: Packet) -> Action {
+        self.y = packet.y;
+        Action::Stop
     }
 
-    println!("day 23.2 - Y value delivered by the NAT to the computer at address 0 twice in a row: {}", y);
+    fn idle(&mut self) -> Action {
+        Action::Continue
+    }
 }
 
-fn init_computer(i: usize, codes: &[i64]) -> IntCode {
-    let mut computer = IntCode::new(codes.to_owned());
-    computer.write_one(i as i64);
-    computer.process();
-    computer
+// Keeps the last packet and sends it to address 0 when the network is idle
+#[derive(Default)]
+struct Monitor {
+    last: Option<Packet>,
+    delivered: Option<i64>
 }
 
-fn network(computers: &mut [IntCode]) -> Packet {
-    let size = computers.len();
-    let mut address = 0;
-    let mut x = 0;
-    let mut y = 0;
+impl Nat for Monitor {
+    fn receive(&mut self, packet: Packet) -> Action {
+        self.last = Some(packet);
+        Action::Continue
+    }
 
-    for i in 0..size {
-        let output = computers[i].read();
-        output.chunks(3).for_each(|p| {
-            let a = p[0] as usize;
-            if a < size {
-                computers[a].write(&[p[1], p[2]]);
-            } else {
-                address = a;
-                x = p[1];
-                y = p[2];
-            }
-        })
-    };
-    Packet { address, x, y }
-}
\ No newline at end of file
+    fn idle(&mut self) -> Action {
+        match self.last {
+            Some(packet) if self.delivered == Some(packet.y) => Action::Stop,
+            Some(packet) => {
+                self.delivered = Some(packet.y);
+                Action::Send(Packet { address: 0, ..packet })
+            },
+            None => Action::Continue
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use std::fs;
+use crate::Answer;
 
 #[derive(Clone)]
 struct Grid {
@@ -144,22 +144,16 @@ impl ToString for Grid {
     }
 }
 
-pub fn first_star() {
-    let contents = fs::read_to_string("./input/day24.txt")
-        .expect("Something went wrong reading the file");
+pub fn first_star(contents: &str) -> Answer {
+    let grid = impl_first_star(contents);
 
-    let grid = impl_first_star(&contents);
-
-    println!("day 24.1 - biodiversity rating for the first layout that appears twice: {}", grid);
+    Answer::from(grid)
 }
 
-pub fn second_star() {
-    let contents = fs::read_to_string("./input/day24.txt")
-        .expect("Something went wrong reading the file");
-
-    let count = impl_second_star(&contents, 200);
+pub fn second_star(contents: &str) -> Answer {
+    let count = impl_second_star(contents, 200);
 
-    println!("day 24.2 - bugs present after 200 minutes: {}", count);
+    Answer::from(count)
 }
 
 fn impl_first_star(contents: &str) -> u32 {
@@ -1,38 +1,51 @@
-use std::fs;
 use std::collections::HashSet;
 use lazy_static::lazy_static;
 use regex::Regex;
-use crate::intcode::{ IntCode, Status, extract_codes, interrupt_after };
+use crate::Answer;
+use crate::intcode::{ IntCode, Limits, Status, extract_codes };
+use crate::session::Session;
 
-pub fn first_star() {
-    let last_message = impl_first_star(false);
+pub fn first_star(contents: &str) -> Answer {
+    let last_message = impl_first_star(contents, false);
 
-    println!("day 25.1 - message containing password for the main airlock: {}", last_message);
+    match parse_password(&last_message) {
+        Some(password) => Answer::from(password),
+        None => Answer::from(last_message)
+    }
+}
+
+pub fn second_star(_contents: &str) -> Answer {
+    Answer::from("Thanks for watching")
 }
 
-pub fn second_star() {
-    println!("day 25.2 - THE END ... Thanks for watching");
+pub fn impl_first_star(contents: &str, verbose: bool) -> String {
+    let mut droid = IntCode::new(extract_codes(contents).unwrap());
+    play(&mut droid, verbose)
 }
 
-pub fn impl_first_star(verbose: bool) -> String {
-    let contents = fs::read_to_string("./input/day25.txt")
-        .expect("Something went wrong reading the file");
+// Session of the droid up to the password, without the attempts it rewound, to replay it
+pub fn session(contents: &str) -> Session {
+    let mut droid = IntCode::new(extract_codes(contents).unwrap());
+    droid.set_session(Session::default());
+    play(&mut droid, false);
+    droid.take_session().unwrap()
+}
 
+fn play(droid: &mut IntCode, verbose: bool) -> String {
     let print = |s: &str| { if verbose { print!("{}", s)} };
 
-    let codes = extract_codes(&contents);
     let target = "Security Checkpoint";
     let mut bad_items = HashSet::new();
-    let mut droid = IntCode::new(codes.clone());
+    let start = droid.snapshot();
     loop {
-        let result = visit_and_collect(&mut droid, None, target, &mut bad_items, &print);
+        let result = visit_and_collect(droid, None, target, &mut bad_items, &print);
         match result {
             Ok(path_to_target) => {
-                let last_door = goto_target_location(&mut droid, &path_to_target, &print);
-                pass_last_door(&mut droid, last_door, &|_| ()); // Never print attempts to cross last door
+                let last_door = goto_target_location(droid, &path_to_target, &print);
+                pass_last_door(droid, last_door, &|_| ()); // Never print attempts to cross last door
                 break;
             },
-            Err(_) => droid = IntCode::new(codes.clone()) // Found bad item, reset and try again
+            Err(_) => droid.restore(&start) // Found bad item, reset and try again
         }
     }
 
@@ -40,7 +53,7 @@ pub fn impl_first_star(verbose: bool) -> String {
 }
 
 fn visit_and_collect(droid: &mut IntCode, from: Option<&str>, target: &str, bad_items: &mut HashSet<String>, print: &impl Fn(&str)) -> Result<Vec<String>, Status> {
-    let mut status = droid.process();
+    let status = droid.process().unwrap();
     let output = droid.read_string();
     print(&output);
 
@@ -50,13 +63,18 @@ fn visit_and_collect(droid: &mut IntCode, from: Option<&str>, target: &str, bad_
         if !bad_items.contains(*item) {
             let command = format!("take {}\n", item);
             print(&command);
+            let before_taking = droid.snapshot();
             droid.write_string(&command);
             // We guess that if droid is still running after 5000 cycles
             // it is stuck in an infinite loop
-            status = droid.process_interruptable(interrupt_after(5000));
-            if status != Status::Waiting {
+            droid.set_limits(Limits { cycles: Some(5000), ..Limits::default() });
+            let take_status = droid.process().unwrap();
+            droid.set_limits(Limits::default());
+            if take_status != Status::Waiting {
+                // Leave the item here and go on as if we never tried to take it
                 bad_items.insert((*item).to_string());
-                return Err(status)
+                droid.restore(&before_taking);
+                continue;
             }
             print(&droid.read_string());
         }
@@ -144,7 +162,7 @@ fn pass_last_door(droid: &mut IntCode, last_door: &str, print: &impl Fn(&str)) {
     // try any combinations of items to have the correct weight
     let mut combinations = (1 << items.len()) - 1;
     // we finish when program ends
-    while droid.process() != Status::End {
+    while droid.process().unwrap() != Status::End {
         print(&droid.read_string());
         combinations -= 1;
         for (i, item) in items.iter_mut().enumerate() {
@@ -168,7 +186,7 @@ fn pass_last_door(droid: &mut IntCode, last_door: &str, print: &impl Fn(&str)) {
 fn go(droid: &mut IntCode, command: &str, print: &impl Fn(&str)) -> String {
     print(&command);
     droid.write_string(&command);
-    droid.process();
+    droid.process().unwrap();
     let output = droid.read_string();
     print(&output);
     output
@@ -194,6 +212,15 @@ fn parse_location(s: &str) -> Option<&str> {
         .map(|c| c.get(1).unwrap().as_str())
 }
 
+fn parse_password(s: &str) -> Option<i64> {
+    lazy_static! {
+        static ref PASSWORD_RE: Regex = Regex::new(r"typing (\d+)").unwrap();
+    }
+    PASSWORD_RE
+        .captures(s)
+        .and_then(|c| c[1].parse().ok())
+}
+
 fn parse_doors(s: &str) -> Vec<&str> {
     lazy_static! {
         static ref DOORS_RE: Regex = Regex::new(r"Doors here lead:((?:\n\- \w+)+)").unwrap();
@@ -0,0 +1,481 @@
+//
+// Debugger for IntCode programs, with single stepping, breakpoints on addresses
+// or opcodes and watchpoints on memory reads and writes
+//
+// Watchpoints fire after the instruction accessing the address has been executed,
+// writes are reported even when the new value is the same as the old one
+//
+// Executed instructions are recorded, so the program can run backwards too, stopping
+// on the instruction at a breakpoint or writing a watched address
+//
+use std::fmt;
+use std::io::{ self, BufRead, Write };
+use crate::history::{ History, Undo };
+use crate::intcode::{ IntCode, IntCodeError, Opcode, Snapshot, Status };
+use crate::disassembler::line_of;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Breakpoint {
+    Address(usize),
+    Opcode(Opcode)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Watch {
+    Read,
+    Write,
+    Access
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Watchpoint {
+    pub address: usize,
+    pub watch: Watch
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    Stepped,
+    Breakpoint(Breakpoint),
+    Read { address: usize, value: i64 },
+    Write { address: usize, old: i64, new: i64 },
+    Waiting,
+    End,
+    // one of the limits of the program
+    Limit(Status),
+    // no recorded instruction left to go back
+    Start
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Event::Stepped => write!(f, "stepped"),
+            Event::Breakpoint(Breakpoint::Address(address)) => write!(f, "breakpoint at address {}", address),
+            Event::Breakpoint(Breakpoint::Opcode(opcode)) => write!(f, "breakpoint on opcode {}", opcode.mnemonic()),
+            Event::Read { address, value } => write!(f, "read {} from address {}", value, address),
+            Event::Write { address, old, new } => write!(f, "write {} to address {} (was {})", new, address, old),
+            Event::Waiting => write!(f, "waiting for input"),
+            Event::End => write!(f, "program ended"),
+            Event::Limit(Status::CycleLimit) => write!(f, "cycle limit reached"),
+            Event::Limit(Status::MemoryLimit) => write!(f, "memory limit reached"),
+            Event::Limit(Status::OutputLimit) => write!(f, "output limit reached"),
+            Event::Limit(status) => write!(f, "{:?}", status),
+            Event::Start => write!(f, "start of the history")
+        }
+    }
+}
+
+// Instructions the debugger can go back
+const HISTORY: usize = 1 << 20;
+
+// Words the x command examines and lines the list command prints at most
+const EXAMINE: i64 = 4096;
+
+pub struct Debugger {
+    program: IntCode,
+    breakpoints: Vec<Breakpoint>,
+    watchpoints: Vec<Watchpoint>
+}
+
+impl Debugger {
+    pub fn new(mut program: IntCode) -> Self {
+        program.set_history(History::new(HISTORY));
+        Debugger { program, breakpoints: Vec::new(), watchpoints: Vec::new() }
+    }
+
+    pub fn program(&self) -> &IntCode {
+        &self.program
+    }
+
+    pub fn program_mut(&mut self) -> &mut IntCode {
+        &mut self.program
+    }
+
+    pub fn cycles(&self) -> u64 {
+        self.program.cycles()
+    }
+
+    pub fn breakpoints(&self) -> &[Breakpoint] {
+        &self.breakpoints
+    }
+
+    pub fn add_breakpoint(&mut self, breakpoint: Breakpoint) {
+        self.breakpoints.push(breakpoint)
+    }
+
+    pub fn remove_breakpoint(&mut self, index: usize) -> Option<Breakpoint> {
+        if index < self.breakpoints.len() { Some(self.breakpoints.remove(index)) } else { None }
+    }
+
+    pub fn watchpoints(&self) -> &[Watchpoint] {
+        &self.watchpoints
+    }
+
+    pub fn add_watchpoint(&mut self, address: usize, watch: Watch) {
+        self.watchpoints.push(Watchpoint { address, watch })
+    }
+
+    pub fn remove_watchpoint(&mut self, index: usize) -> Option<Watchpoint> {
+        if index < self.watchpoints.len() { Some(self.watchpoints.remove(index)) } else { None }
+    }
+
+    // Executes one instruction, ignoring breakpoints
+    pub fn step(&mut self) -> Result<Event, IntCodeError> {
+        // an instruction that cannot be decoded fails in step with a proper error
+        let accesses = self.program.accesses().unwrap_or_default();
+        let reads = accesses.reads.iter().map(|&a| (a, self.program.peek(a))).collect::<Vec<_>>();
+        let old = accesses.write.map(|a| self.program.peek(a));
+
+        match self.program.step()? {
+            Status::Running => {
+                for watchpoint in &self.watchpoints {
+                    if watchpoint.watch != Watch::Read && accesses.write == Some(watchpoint.address) {
+                        let new = self.program.peek(watchpoint.address);
+                        return Ok(Event::Write { address: watchpoint.address, old: old.unwrap(), new });
+                    }
+                    if watchpoint.watch != Watch::Write {
+                        if let Some(&(address, value)) = reads.iter().find(|(a, _)| *a == watchpoint.address) {
+                            return Ok(Event::Read { address, value });
+                        }
+                    }
+                }
+                Ok(Event::Stepped)
+            },
+            Status::Waiting => Ok(Event::Waiting),
+            Status::End => Ok(Event::End),
+            status => Ok(Event::Limit(status))
+        }
+    }
+
+    // Executes instructions until a breakpoint or a watchpoint is hit, the program
+    // waits for input or ends. A breakpoint on the current instruction is stepped over
+    pub fn run(&mut self) -> Result<Event, IntCodeError> {
+        let mut first = true;
+        loop {
+            if !first {
+                if let Some(breakpoint) = self.breakpoint_hit() {
+                    return Ok(Event::Breakpoint(breakpoint));
+                }
+            }
+            first = false;
+            match self.step()? {
+                Event::Stepped => (),
+                event => return Ok(event)
+            }
+        }
+    }
+
+    // Undoes the last executed instruction
+    pub fn step_back(&mut self) -> Option<Undo> {
+        self.program.step_back()
+    }
+
+    // Goes back until the instruction at a breakpoint or writing a watched address is the
+    // next one to execute, or there's no instruction left to go back
+    pub fn run_back(&mut self) -> Event {
+        loop {
+            let write = self.program.history().and_then(|history| history.last()).and_then(|undo| undo.write);
+            let new = write.map(|(address, _)| self.program.peek(address));
+            if self.step_back().is_none() {
+                return Event::Start;
+            }
+            if let Some((address, old)) = write {
+                if self.watchpoints.iter().any(|w| w.watch != Watch::Read && w.address == address) {
+                    return Event::Write { address, old, new: new.unwrap() };
+                }
+            }
+            if let Some(breakpoint) = self.breakpoint_hit() {
+                return Event::Breakpoint(breakpoint);
+            }
+        }
+    }
+
+    fn breakpoint_hit(&self) -> Option<Breakpoint> {
+        let pos = self.program.pos();
+        let opcode = Opcode::from_code(self.program.peek(pos) % 100);
+        self.breakpoints
+            .iter()
+            .copied()
+            .find(|b| match b {
+                Breakpoint::Address(address) => *address == pos,
+                Breakpoint::Opcode(op) => Some(*op) == opcode
+            })
+    }
+}
+
+const HELP: &str = "\
+step [n]              execute n instructions (default 1)
+continue              run until a breakpoint, a watchpoint, input is needed or the end
+back [n]              undo the last n instructions (default 1)
+reverse               run backwards until a breakpoint or a write watchpoint
+break <addr|opcode>   add a breakpoint on an address or an opcode mnemonic (e.g. IN)
+watch <addr> [r|w|rw] add a watchpoint on reads, writes or both (default rw)
+delete <n>            remove breakpoint n
+unwatch <n>           remove watchpoint n
+info                  show position, relative base, queues, breakpoints and watchpoints
+x <addr> [len]        examine len words of memory (default 1, at most 4096)
+list [addr] [n]       disassemble n instructions from addr (default current position and 10, at most 4096)
+input <v1,v2,...>     append integers to the input queue
+text <string>         append an ASCII line to the input queue
+output                read the output queue
+save <file>           save the machine state to a file
+load <file>           restore the machine state from a file
+quit                  exit the debugger
+An empty line repeats the last command";
+
+pub fn repl(debugger: &mut Debugger, input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+    let mut last = String::new();
+    write!(output, "{}\n(icdb) ", current_line(debugger))?;
+    output.flush()?;
+    for line in input.lines() {
+        let line = line?;
+        let command = if line.trim().is_empty() { last.clone() } else { line.trim().to_string() };
+        let (name, args) = command.split_at(command.find(' ').unwrap_or(command.len()));
+        let args = args.trim();
+        let numbers = args.split_whitespace().map(|a| a.parse::<i64>().ok()).collect::<Vec<_>>();
+        let number = |i: usize, default: i64| numbers.get(i).copied().unwrap_or(Some(default));
+
+        match name {
+            "s" | "step" => match number(0, 1) {
+                Some(n) => {
+                    let mut event = Ok(Event::Stepped);
+                    for _ in 0..n.max(1) {
+                        event = debugger.step();
+                        if event != Ok(Event::Stepped) { break }
+                    }
+                    report(debugger, event, &mut output)?;
+                },
+                None => writeln!(output, "wrong number of steps {}", args)?
+            },
+            "c" | "continue" => {
+                let event = debugger.run();
+                report(debugger, event, &mut output)?;
+            },
+            "back" => match number(0, 1) {
+                Some(n) => {
+                    let event = if (0..n.max(1)).all(|_| debugger.step_back().is_some()) { Event::Stepped } else { Event::Start };
+                    report(debugger, Ok(event), &mut output)?;
+                },
+                None => writeln!(output, "wrong number of steps {}", args)?
+            },
+            "r" | "reverse" => {
+                let event = debugger.run_back();
+                report(debugger, Ok(event), &mut output)?;
+            },
+            "b" | "break" => match (args.parse::<usize>(), Opcode::from_mnemonic(args)) {
+                (Ok(address), _) => debugger.add_breakpoint(Breakpoint::Address(address)),
+                (_, Some(opcode)) => debugger.add_breakpoint(Breakpoint::Opcode(opcode)),
+                _ => writeln!(output, "wrong breakpoint {}", args)?
+            },
+            "w" | "watch" => {
+                let mut parts = args.split_whitespace();
+                let address = parts.next().and_then(|a| a.parse::<usize>().ok());
+                let watch = match parts.next() {
+                    Some("r") => Some(Watch::Read),
+                    Some("w") => Some(Watch::Write),
+                    Some("rw") | None => Some(Watch::Access),
+                    _ => None
+                };
+                match (address, watch) {
+                    (Some(address), Some(watch)) => debugger.add_watchpoint(address, watch),
+                    _ => writeln!(output, "wrong watchpoint {}", args)?
+                }
+            },
+            "d" | "delete" => if args.parse::<usize>().ok().and_then(|i| debugger.remove_breakpoint(i)).is_none() {
+                writeln!(output, "no breakpoint {}", args)?
+            },
+            "unwatch" => if args.parse::<usize>().ok().and_then(|i| debugger.remove_watchpoint(i)).is_none() {
+                writeln!(output, "no watchpoint {}", args)?
+            },
+            "i" | "info" => {
+                let program = debugger.program();
+                writeln!(output, "pos {}, base {}, cycles {}", program.pos(), program.base(), debugger.cycles())?;
+                writeln!(output, "inputs {:?}", program.inputs())?;
+                writeln!(output, "outputs {:?}", program.outputs())?;
+                for (i, b) in debugger.breakpoints().iter().enumerate() {
+                    writeln!(output, "breakpoint {}: {:?}", i, b)?;
+                }
+                for (i, w) in debugger.watchpoints().iter().enumerate() {
+                    writeln!(output, "watchpoint {}: {:?} {}", i, w.watch, w.address)?;
+                }
+            },
+            "x" => match (number(0, -1), number(1, 1)) {
+                (Some(address), Some(len)) if address >= 0 && (0..=EXAMINE).contains(&len) && address.checked_add(len).is_some() => {
+                    let values = (address..address + len)
+                        .map(|a| debugger.program().peek(a as usize).to_string())
+                        .collect::<Vec<_>>();
+                    writeln!(output, "{:>5}: {}", address, values.join(", "))?
+                },
+                _ => writeln!(output, "wrong memory range {}", args)?
+            },
+            "l" | "list" => match (number(0, debugger.program().pos() as i64), number(1, 10)) {
+                (Some(address), Some(n)) if address >= 0 && (0..=EXAMINE).contains(&n) => {
+                    let program = debugger.program();
+                    let mut address = address as usize;
+                    for _ in 0..n {
+                        let line = line_of(|a| program.word(a), address);
+                        writeln!(output, "{}", line)?;
+                        address += line.size();
+                    }
+                },
+                _ => writeln!(output, "wrong listing {}", args)?
+            },
+            "input" => match args.split(',').map(|v| v.trim().parse::<i64>()).collect::<Result<Vec<_>, _>>() {
+                Ok(values) => debugger.program_mut().write(&values),
+                Err(_) => writeln!(output, "wrong input {}", args)?
+            },
+            "text" => debugger.program_mut().write_string(&format!("{}\n", args)),
+            "o" | "output" => {
+                let values = debugger.program_mut().read();
+                if values.iter().all(|&v| v == 10 || (32..127).contains(&v)) {
+                    write!(output, "{}", values.iter().map(|&v| v as u8 as char).collect::<String>())?;
+                }
+                writeln!(output, "{:?}", values)?
+            },
+            "save" => if let Err(e) = debugger.program().snapshot().save(args) {
+                writeln!(output, "cannot save {}: {}", args, e)?
+            },
+            "load" => match Snapshot::load(args) {
+                Ok(snapshot) => {
+                    debugger.program_mut().restore(&snapshot);
+                    writeln!(output, "{}", current_line(debugger))?
+                },
+                Err(e) => writeln!(output, "cannot load {}: {}", args, e)?
+            },
+            "q" | "quit" => return Ok(()),
+            "h" | "help" => writeln!(output, "{}", HELP)?,
+            "" => (),
+            _ => writeln!(output, "unknown command {}, type help for the list of commands", name)?
+        }
+        last = command;
+        write!(output, "(icdb) ")?;
+        output.flush()?;
+    }
+    Ok(())
+}
+
+fn current_line(debugger: &Debugger) -> String {
+    let program = debugger.program();
+    line_of(|address| program.word(address), program.pos()).to_string()
+}
+
+fn report(debugger: &Debugger, event: Result<Event, IntCodeError>, output: &mut impl Write) -> io::Result<()> {
+    match event {
+        Ok(Event::Stepped) => writeln!(output, "{}", current_line(debugger)),
+        Ok(event) => writeln!(output, "{}\n{}", event, current_line(debugger)),
+        Err(e) => writeln!(output, "error: {}", e)
+    }
+}
+
+#[cfg(test)]
+const COUNTDOWN: [i64; 13] = [3,12,4,12,1001,12,-1,12,1005,12,2,99,0];
+
+#[test]
+fn test0_step() {
+    let mut debugger = Debugger::new(IntCode::new(COUNTDOWN.to_vec()));
+    assert_eq!(debugger.step(), Ok(Event::Waiting));
+    debugger.program_mut().write_one(2);
+    assert_eq!(debugger.step(), Ok(Event::Stepped));
+    assert_eq!(debugger.program().pos(), 2);
+    assert_eq!(debugger.program().peek(12), 2);
+    assert_eq!(debugger.cycles(), 1);
+}
+
+#[test]
+fn test1_breakpoint() {
+    let mut debugger = Debugger::new(IntCode::new(COUNTDOWN.to_vec()));
+    debugger.program_mut().write_one(3);
+    debugger.add_breakpoint(Breakpoint::Address(8));
+    debugger.add_breakpoint(Breakpoint::Opcode(Opcode::Hlt));
+    assert_eq!(debugger.run(), Ok(Event::Breakpoint(Breakpoint::Address(8))));
+    assert_eq!(debugger.run(), Ok(Event::Breakpoint(Breakpoint::Address(8))));
+    assert_eq!(debugger.program_mut().read(), [3, 2]);
+    debugger.remove_breakpoint(0);
+    assert_eq!(debugger.run(), Ok(Event::Breakpoint(Breakpoint::Opcode(Opcode::Hlt))));
+    assert_eq!(debugger.run(), Ok(Event::End));
+    assert_eq!(debugger.program_mut().read(), [1]);
+}
+
+#[test]
+fn test2_watchpoint() {
+    let mut debugger = Debugger::new(IntCode::new(COUNTDOWN.to_vec()));
+    debugger.program_mut().write_one(3);
+    debugger.add_watchpoint(12, Watch::Write);
+    assert_eq!(debugger.run(), Ok(Event::Write { address: 12, old: 0, new: 3 }));
+    assert_eq!(debugger.run(), Ok(Event::Write { address: 12, old: 3, new: 2 }));
+    debugger.remove_watchpoint(0);
+    debugger.add_watchpoint(12, Watch::Read);
+    assert_eq!(debugger.run(), Ok(Event::Read { address: 12, value: 2 }));
+    assert_eq!(debugger.program().pos(), 2);
+}
+
+#[test]
+fn test0_repl() {
+    let mut debugger = Debugger::new(IntCode::new(COUNTDOWN.to_vec()));
+    let mut output = Vec::new();
+    repl(&mut debugger, "input 1\nbreak OUT\ncontinue\n\nx 12\nquit\n".as_bytes(), &mut output).unwrap();
+    assert_eq!(String::from_utf8(output).unwrap(), "    \
+            0: IN   12\n(icdb) (icdb) (icdb) \
+            breakpoint on opcode OUT\n    \
+            2: OUT  12\n(icdb) \
+            program ended\n   \
+           11: HLT\n(icdb)    \
+           12: 0\n(icdb) ");
+}
+
+#[test]
+fn test1_repl() {
+    let mut debugger = Debugger::new(IntCode::new(COUNTDOWN.to_vec()));
+    let mut output = Vec::new();
+    repl(&mut debugger, "x 11 2\nx 9223372036854775807 2\nx 0 1000000000000\nx 0 -1\nlist 8 2\nlist 0 1000000000000\nlist 0 -1\n".as_bytes(), &mut output).unwrap();
+    assert_eq!(String::from_utf8(output).unwrap(), "    \
+            0: IN   12\n(icdb)    \
+           11: 99, 0\n(icdb) \
+        wrong memory range 9223372036854775807 2\n(icdb) \
+        wrong memory range 0 1000000000000\n(icdb) \
+        wrong memory range 0 -1\n(icdb)     \
+            8: JNZ  12, #2\n   \
+           11: HLT\n(icdb) \
+        wrong listing 0 1000000000000\n(icdb) \
+        wrong listing 0 -1\n(icdb) ");
+}
+
+#[test]
+fn test3_run_back() {
+    let mut debugger = Debugger::new(IntCode::new(COUNTDOWN.to_vec()));
+    debugger.program_mut().write_one(3);
+    assert_eq!(debugger.run(), Ok(Event::End));
+    assert_eq!(debugger.cycles(), 10);
+
+    // back to the write of 1 to the counter, then to the input
+    debugger.add_watchpoint(12, Watch::Write);
+    assert_eq!(debugger.run_back(), Event::Write { address: 12, old: 1, new: 0 });
+    assert_eq!(debugger.program().pos(), 4);
+    assert_eq!(debugger.program().peek(12), 1);
+    assert_eq!(debugger.program().outputs(), &[3, 2, 1]);
+    debugger.remove_watchpoint(0);
+    debugger.add_breakpoint(Breakpoint::Opcode(Opcode::In));
+    assert_eq!(debugger.run_back(), Event::Breakpoint(Breakpoint::Opcode(Opcode::In)));
+    assert_eq!((debugger.program().pos(), debugger.cycles()), (0, 0));
+    assert_eq!(debugger.program().inputs(), &[3]);
+    assert!(debugger.program().outputs().is_empty());
+    assert_eq!(debugger.run_back(), Event::Start);
+
+    // and forward again the same way
+    debugger.remove_breakpoint(0);
+    assert_eq!(debugger.run(), Ok(Event::End));
+    assert_eq!(debugger.program_mut().read(), [3, 2, 1]);
+}
+
+#[test]
+fn test0_cycles() {
+    // steps taken by the program itself are recorded and counted all the same
+    let mut debugger = Debugger::new(IntCode::new(COUNTDOWN.to_vec()));
+    debugger.program_mut().write_one(1);
+    assert_eq!(debugger.program_mut().process(), Ok(Status::End));
+    assert_eq!(debugger.cycles(), 4);
+    assert_eq!(debugger.run_back(), Event::Start);
+    assert_eq!((debugger.program().pos(), debugger.cycles()), (0, 0));
+    assert_eq!(debugger.step_back(), None);
+}
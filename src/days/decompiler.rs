@@ -0,0 +1,810 @@
+//
+// Decompiler of IntCode programs to structured pseudo-code
+//
+// The functions found by the analysis are lifted block by block into statements, then
+// their control flow is structured back into loops and conditionals, following the
+// order of the blocks in memory, jumps that don't fit are left as gotos. Memory gets
+// names, by function:
+//   - gN for the global at address N
+//   - argN and localN for the slots of the frame, arguments being the first ones
+//   - outN for the slots past the frame, the arguments of the calls and their results
+//   - pN for the operand at address N, which the program patches, mem[pN] reading the
+//     memory it points to
+// Calls are recognized by the stack convention of the analysis: the return address and
+// the arguments written just before the jump become the call
+//
+use std::collections::{ BTreeMap, BTreeSet };
+use std::convert::TryFrom;
+use std::fmt::Write;
+use crate::analysis::{ self, Exit, Function, Graph };
+use crate::disassembler::decode_at;
+use crate::intcode::{ Mode, Opcode };
+
+const INDENT: &str = "    ";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Slot {
+    Global(usize),
+    // relative to the base of the function
+    Frame(i64),
+    // operand patched by the program
+    Operand(usize)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Add,
+    Mul,
+    Lt,
+    Ge,
+    Eq,
+    Ne
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Const(i64),
+    Slot(Slot),
+    // memory at a computed address, absolute or relative
+    Mem(Box<Expr>),
+    Frame(Box<Expr>),
+    Input,
+    Binary(Op, Box<Expr>, Box<Expr>)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Callee {
+    Function(usize),
+    Pointer(Expr)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Stmt {
+    Assign(Expr, Expr),
+    Output(Expr),
+    Call(Callee, Vec<Expr>),
+    // moves of the relative base other than the ones of the frame
+    Base(Expr),
+    If(Expr, Vec<Stmt>, Vec<Stmt>),
+    While(Expr, Vec<Stmt>),
+    DoWhile(Vec<Stmt>, Expr),
+    Loop(Vec<Stmt>),
+    Break,
+    Continue,
+    Label(usize),
+    Goto(usize),
+    GotoComputed(Expr),
+    // runs into words that are not instructions, which the program may write before
+    Unknown(usize),
+    Return,
+    Halt
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decompiled {
+    pub entry: usize,
+    pub frame: Option<i64>,
+    pub params: usize,
+    pub body: Vec<Stmt>
+}
+
+impl Expr {
+    fn binary(op: Op, left: Expr, right: Expr) -> Expr {
+        match (op, left, right) {
+            (Op::Add, Expr::Const(a), Expr::Const(b)) if a.checked_add(b).is_some() => Expr::Const(a + b),
+            (Op::Mul, Expr::Const(a), Expr::Const(b)) if a.checked_mul(b).is_some() => Expr::Const(a * b),
+            (Op::Add, Expr::Const(0), x) | (Op::Add, x, Expr::Const(0)) => x,
+            (Op::Mul, Expr::Const(1), x) | (Op::Mul, x, Expr::Const(1)) => x,
+            (op, left, right) => Expr::Binary(op, Box::new(left), Box::new(right))
+        }
+    }
+
+    // Condition of a value being true, as tested by JNZ
+    fn truthy(self) -> Expr {
+        match self {
+            Expr::Binary(Op::Lt | Op::Ge | Op::Eq | Op::Ne, _, _) => self,
+            x => Expr::Binary(Op::Ne, Box::new(x), Box::new(Expr::Const(0)))
+        }
+    }
+
+    fn negate(self) -> Expr {
+        match self {
+            Expr::Binary(op @ (Op::Lt | Op::Ge | Op::Eq | Op::Ne), left, right) => {
+                let op = match op {
+                    Op::Lt => Op::Ge,
+                    Op::Ge => Op::Lt,
+                    Op::Eq => Op::Ne,
+                    _ => Op::Eq
+                };
+                Expr::Binary(op, left, right)
+            },
+            x => Expr::Binary(Op::Eq, Box::new(x), Box::new(Expr::Const(0)))
+        }
+    }
+
+    // Slots read to evaluate the expression
+    fn reads(&self, slots: &mut Vec<Slot>) {
+        match self {
+            Expr::Slot(slot) => slots.push(*slot),
+            Expr::Mem(x) | Expr::Frame(x) => x.reads(slots),
+            Expr::Binary(_, left, right) => {
+                left.reads(slots);
+                right.reads(slots);
+            },
+            Expr::Const(_) | Expr::Input => ()
+        }
+    }
+}
+
+impl Stmt {
+    // Slots read by the statement, the inner ones of the places it writes included
+    fn reads(&self) -> Vec<Slot> {
+        let mut slots = Vec::new();
+        match self {
+            Stmt::Assign(place, value) => {
+                if let Expr::Mem(x) | Expr::Frame(x) = place {
+                    x.reads(&mut slots);
+                }
+                value.reads(&mut slots);
+            },
+            Stmt::Output(x) | Stmt::Base(x) | Stmt::GotoComputed(x) => x.reads(&mut slots),
+            Stmt::Call(callee, args) => {
+                if let Callee::Pointer(x) = callee {
+                    x.reads(&mut slots);
+                }
+                args.iter().for_each(|arg| arg.reads(&mut slots));
+            },
+            _ => ()
+        }
+        slots
+    }
+
+    fn writes(&self) -> Option<Slot> {
+        match self {
+            Stmt::Assign(Expr::Slot(slot), _) => Some(*slot),
+            _ => None
+        }
+    }
+}
+
+// Value of the parameter of the instruction at the address, or the place it writes to
+fn operand(codes: &[i64], patched: &BTreeSet<usize>, address: usize, param: usize) -> Expr {
+    let instruction = decode_at(codes, address).unwrap();
+    let word = address + param;
+    let value = codes[word];
+    let slot = Expr::Slot(Slot::Operand(word));
+    match (instruction.modes[param - 1], patched.contains(&word)) {
+        (Mode::Immediate, false) => Expr::Const(value),
+        (Mode::Immediate, true) => slot,
+        (Mode::Position, false) => match usize::try_from(value) {
+            Ok(address) if patched.contains(&address) => Expr::Slot(Slot::Operand(address)),
+            Ok(address) => Expr::Slot(Slot::Global(address)),
+            Err(_) => Expr::Mem(Box::new(Expr::Const(value)))
+        },
+        (Mode::Position, true) => Expr::Mem(Box::new(slot)),
+        (Mode::Relative, false) => Expr::Slot(Slot::Frame(value)),
+        (Mode::Relative, true) => Expr::Frame(Box::new(slot))
+    }
+}
+
+// Statement of an instruction, jumps and HLT are the exits of their blocks
+fn lift(codes: &[i64], patched: &BTreeSet<usize>, address: usize) -> Option<Stmt> {
+    let instruction = decode_at(codes, address).unwrap();
+    let param = |param: usize| operand(codes, patched, address, param);
+    let place = |index: usize| match instruction.modes[index - 1] {
+        // immediate writes go to the operand itself
+        Mode::Immediate => Expr::Slot(Slot::Operand(address + index)),
+        _ => param(index)
+    };
+    let op = match instruction.opcode {
+        Opcode::Add => Op::Add,
+        Opcode::Mul => Op::Mul,
+        Opcode::Lt => Op::Lt,
+        Opcode::Eq => Op::Eq,
+        Opcode::In => return Some(Stmt::Assign(place(1), Expr::Input)),
+        Opcode::Out => return Some(Stmt::Output(param(1))),
+        Opcode::Arb => return Some(Stmt::Base(param(1))),
+        Opcode::Jnz | Opcode::Jz | Opcode::Hlt => return None
+    };
+    let (place, value) = (place(3), Expr::binary(op, param(1), param(2)));
+    // copies of a slot to itself do nothing
+    if place == value { None } else { Some(Stmt::Assign(place, value)) }
+}
+
+// Block lifted to statements, with the condition of its jump when it's a branch
+struct Piece {
+    start: usize,
+    // address after the last instruction
+    end: usize,
+    stmts: Vec<Stmt>,
+    exit: Exit,
+    cond: Option<Expr>,
+    // computed target of the jump
+    target: Option<Expr>
+}
+
+fn pieces(codes: &[i64], graph: &Graph, function: &Function, patched: &BTreeSet<usize>) -> Vec<Piece> {
+    let mut pieces = Vec::new();
+    for start in &function.blocks {
+        let block = &graph.blocks[start];
+        let mut stmts = block.instructions.iter().filter_map(|&address| lift(codes, patched, address)).collect::<Vec<_>>();
+        if *start == function.entry && function.frame.is_some() && matches!(stmts.first(), Some(Stmt::Base(_))) {
+            stmts.remove(0);
+        }
+        let last = *block.instructions.last().unwrap();
+        let jump = decode_at(codes, last).filter(|instruction| matches!(instruction.opcode, Opcode::Jnz | Opcode::Jz));
+        let cond = jump.map(|instruction| {
+            let cond = operand(codes, patched, last, 1).truthy();
+            if instruction.opcode == Opcode::Jz { cond.negate() } else { cond }
+        });
+        let target = jump.map(|_| operand(codes, patched, last, 2));
+        match block.exit {
+            Exit::Return if function.frame.is_some() && matches!(stmts.last(), Some(Stmt::Base(Expr::Const(n))) if Some(-n) == function.frame) => {
+                stmts.pop();
+            },
+            Exit::Call { target: callee, ret } => {
+                // the target of the jump can be a patched operand
+                let callee = match (callee, &target) {
+                    (Some(callee), Some(Expr::Const(_))) => Callee::Function(callee),
+                    _ => Callee::Pointer(target.clone().unwrap())
+                };
+                let args = call_arguments(&mut stmts, ret);
+                stmts.push(Stmt::Call(callee, args));
+            },
+            _ => ()
+        }
+        let end = block.instructions.last().map_or(*start, |&address| address + decode_at(codes, address).unwrap().size());
+        pieces.push(Piece { start: *start, end, stmts, exit: block.exit, cond, target });
+    }
+    pieces
+}
+
+// Takes the push of the return address and the last writes of the arguments out of the
+// statements before a call, when they can be evaluated at the call: nothing reads the
+// argument or writes what it reads after them. Other arguments are passed by their slots
+fn call_arguments(stmts: &mut Vec<Stmt>, ret: usize) -> Vec<Expr> {
+    if let Some(i) = stmts.iter().rposition(|stmt| *stmt == Stmt::Assign(Expr::Slot(Slot::Frame(0)), Expr::Const(ret as i64))) {
+        stmts.remove(i);
+    }
+    let count = stmts.iter().filter_map(|stmt| match stmt.writes() {
+        Some(Slot::Frame(k)) if k > 0 => Some(k),
+        _ => None
+    }).max().unwrap_or(0);
+    let mut args = Vec::new();
+    let mut taken = BTreeSet::new();
+    for k in 1..=count {
+        let slot = Slot::Frame(k);
+        let last = stmts.iter().rposition(|stmt| stmt.writes() == Some(slot)).filter(|&i| {
+            let reads = stmts[i].reads();
+            stmts[i + 1..].iter().all(|stmt| !stmt.reads().contains(&slot) && stmt.writes().is_none_or(|slot| !reads.contains(&slot)))
+        });
+        match last {
+            Some(i) => {
+                taken.insert(i);
+                args.push(match &stmts[i] {
+                    Stmt::Assign(_, value) => value.clone(),
+                    _ => unreachable!()
+                });
+            },
+            None => args.push(Expr::Slot(slot))
+        }
+    }
+    let mut i = 0;
+    stmts.retain(|_| {
+        i += 1;
+        !taken.contains(&(i - 1))
+    });
+    args
+}
+
+// Comparison assigned to a slot by the last statement of the piece and tested by its
+// branch, whether it jumps when the comparison holds
+fn tested(piece: &Piece) -> Option<(Slot, &Expr, bool)> {
+    let (slot, jnz) = match &piece.cond {
+        Some(Expr::Binary(op, x, zero)) if **zero == Expr::Const(0) => match **x {
+            Expr::Slot(slot) => (slot, *op == Op::Ne),
+            _ => return None
+        },
+        _ => return None
+    };
+    match piece.stmts.last() {
+        Some(Stmt::Assign(Expr::Slot(place), value @ Expr::Binary(Op::Lt | Op::Eq, _, _))) if *place == slot => Some((slot, value, jnz)),
+        _ => None
+    }
+}
+
+// Counts of the reads of the slots, and of the ones by the branches testing them
+fn usage(pieces: &[Piece], reads: &mut BTreeMap<Slot, usize>, tests: &mut BTreeMap<Slot, usize>) {
+    for piece in pieces {
+        let mut slots = piece.stmts.iter().flat_map(|stmt| stmt.reads()).collect::<Vec<_>>();
+        piece.cond.iter().chain(&piece.target).for_each(|x| x.reads(&mut slots));
+        for slot in slots {
+            *reads.entry(slot).or_insert(0) += 1;
+        }
+        if let Some((slot, _, _)) = tested(piece) {
+            *tests.entry(slot).or_insert(0) += 1;
+        }
+    }
+}
+
+// Comparisons written to a slot that only the branches right after them read become their
+// conditions, the slot is a temporary
+fn fold_conditions(pieces: &mut [Piece], temporary: impl Fn(Slot) -> bool) {
+    for piece in pieces.iter_mut() {
+        let (cond, slot) = match tested(piece) {
+            Some((slot, value, jnz)) => (if jnz { value.clone() } else { value.clone().negate() }, slot),
+            None => continue
+        };
+        if temporary(slot) {
+            piece.stmts.pop();
+            piece.cond = Some(cond);
+        }
+    }
+}
+
+// Loop the statements are in, to turn its jumps into breaks and continues
+#[derive(Clone, Copy)]
+struct Scope {
+    header: usize,
+    exit: usize
+}
+
+struct Structurer<'a> {
+    pieces: &'a [Piece],
+    consumed: BTreeSet<usize>,
+    labels: BTreeSet<usize>,
+    gotos: BTreeSet<usize>
+}
+
+impl<'a> Structurer<'a> {
+    fn index(&self, address: usize, from: usize, to: usize) -> Option<usize> {
+        (from..=to).find(|&i| self.pieces.get(i).map_or(usize::MAX, |piece| piece.start) == address)
+    }
+
+    // Address following the pieces before the index
+    fn start(&self, i: usize) -> usize {
+        self.pieces.get(i).map_or(usize::MAX, |piece| piece.start)
+    }
+
+    fn region(&mut self, from: usize, to: usize, scope: Option<Scope>) -> Vec<Stmt> {
+        let mut stmts = Vec::new();
+        let mut i = from;
+        while i < to {
+            i = self.looped(i, to, scope, &mut stmts);
+        }
+        stmts
+    }
+
+    // Statements from the piece at i, a loop when later pieces jump back to it
+    fn looped(&mut self, i: usize, to: usize, scope: Option<Scope>, stmts: &mut Vec<Stmt>) -> usize {
+        let header = self.pieces[i].start;
+        let back = (i..to).rev().find(|&l| !self.consumed.contains(&l) && match self.pieces[l].exit {
+            Exit::Jump(target) => target == header,
+            Exit::Branch { target, .. } => target == Some(header),
+            _ => false
+        });
+        let l = match back {
+            Some(l) => l,
+            None => return self.piece(i, to, scope, stmts)
+        };
+        let inner = Some(Scope { header, exit: self.start(l + 1) });
+        self.consumed.insert(l);
+        if self.labels.contains(&header) {
+            stmts.push(Stmt::Label(header));
+        }
+        let piece = &self.pieces[i];
+        match (piece.exit, &self.pieces[l].exit) {
+            (Exit::Branch { target: Some(target), .. }, Exit::Jump(_)) if target == self.start(l + 1) && l > i => {
+                self.consumed.insert(i);
+                let cond = piece.cond.clone().unwrap();
+                let header_stmts = piece.stmts.clone();
+                let mut body = self.region(i + 1, l + 1, inner);
+                if header_stmts.is_empty() {
+                    stmts.push(Stmt::While(cond.negate(), body));
+                } else {
+                    let mut stmts_loop = header_stmts;
+                    stmts_loop.push(Stmt::If(cond, vec![Stmt::Break], Vec::new()));
+                    stmts_loop.append(&mut body);
+                    stmts.push(Stmt::Loop(stmts_loop));
+                }
+            },
+            (_, Exit::Branch { .. }) => {
+                let cond = self.pieces[l].cond.clone().unwrap();
+                let mut body = Vec::new();
+                let mut j = self.piece(i, l + 1, inner, &mut body);
+                while j < l + 1 {
+                    j = self.looped(j, l + 1, inner, &mut body);
+                }
+                stmts.push(Stmt::DoWhile(body, cond));
+            },
+            _ => {
+                let mut body = Vec::new();
+                let mut j = self.piece(i, l + 1, inner, &mut body);
+                while j < l + 1 {
+                    j = self.looped(j, l + 1, inner, &mut body);
+                }
+                stmts.push(Stmt::Loop(body));
+            }
+        }
+        l + 1
+    }
+
+    // Statements of the piece at i and its exit, returns the index of the next piece
+    fn piece(&mut self, i: usize, to: usize, scope: Option<Scope>, stmts: &mut Vec<Stmt>) -> usize {
+        let piece = &self.pieces[i];
+        if self.labels.contains(&piece.start) && !matches!(stmts.last(), Some(Stmt::Label(label)) if *label == piece.start) {
+            stmts.push(Stmt::Label(piece.start));
+        }
+        stmts.extend(piece.stmts.iter().cloned());
+        if self.consumed.contains(&i) {
+            return i + 1;
+        }
+        let next = self.start(i + 1);
+        match piece.exit {
+            Exit::Next(_) | Exit::Call { .. } => (),
+            Exit::End => stmts.push(Stmt::Unknown(piece.end)),
+            Exit::Jump(target) if target == next => (),
+            Exit::Jump(target) => stmts.push(self.jump(target, scope)),
+            Exit::Branch { target: Some(target), .. } => {
+                let cond = piece.cond.clone().unwrap();
+                if scope.is_some_and(|scope| target == scope.exit || target == scope.header) || target <= piece.start {
+                    stmts.push(Stmt::If(cond, vec![self.jump(target, scope)], Vec::new()));
+                    return i + 1;
+                }
+                let k = match self.index(target, i + 1, to) {
+                    Some(k) => k,
+                    None => {
+                        stmts.push(Stmt::If(cond, vec![self.jump(target, scope)], Vec::new()));
+                        return i + 1;
+                    }
+                };
+                // else branch, when the then branch jumps over it
+                let over = match self.pieces[k - 1].exit {
+                    Exit::Jump(end) if k - 1 > i && end > target && !self.consumed.contains(&(k - 1)) => self.index(end, k, to),
+                    _ => None
+                };
+                if let Some(m) = over {
+                    self.consumed.insert(k - 1);
+                    let then = self.region(i + 1, k, scope);
+                    let otherwise = self.region(k, m, scope);
+                    stmts.push(Stmt::If(cond.negate(), then, otherwise));
+                    return m;
+                }
+                let then = self.region(i + 1, k, scope);
+                stmts.push(Stmt::If(cond.negate(), then, Vec::new()));
+                return k;
+            },
+            Exit::Branch { target: None, .. } => {
+                let cond = piece.cond.clone().unwrap();
+                stmts.push(Stmt::If(cond, vec![Stmt::GotoComputed(piece.target.clone().unwrap())], Vec::new()));
+            },
+            Exit::Return => stmts.push(Stmt::Return),
+            Exit::Halt => stmts.push(Stmt::Halt),
+            Exit::Indirect => stmts.push(Stmt::GotoComputed(piece.target.clone().unwrap()))
+        }
+        i + 1
+    }
+
+    fn jump(&mut self, target: usize, scope: Option<Scope>) -> Stmt {
+        match scope {
+            Some(scope) if target == scope.exit => Stmt::Break,
+            Some(scope) if target == scope.header => Stmt::Continue,
+            _ => {
+                self.gotos.insert(target);
+                Stmt::Goto(target)
+            }
+        }
+    }
+}
+
+// Structures the pieces twice, the first time to find the targets of the gotos. Labels
+// are only placed, they don't change the structure, so the second time has the same gotos
+fn structure(pieces: &[Piece]) -> Vec<Stmt> {
+    let mut first = Structurer { pieces, consumed: BTreeSet::new(), labels: BTreeSet::new(), gotos: BTreeSet::new() };
+    first.region(0, pieces.len(), None);
+    let mut second = Structurer { pieces, consumed: BTreeSet::new(), labels: first.gotos, gotos: BTreeSet::new() };
+    let stmts = second.region(0, pieces.len(), None);
+    debug_assert_eq!(second.gotos, second.labels);
+    stmts
+}
+
+pub fn decompile(codes: &[i64]) -> Vec<Decompiled> {
+    let graph = analysis::analyze(codes);
+    let patched = graph.patches.iter().map(|patch| patch.address).collect::<BTreeSet<_>>();
+    let functions = graph.functions.iter().map(|function| (function, pieces(codes, &graph, function, &patched))).collect::<Vec<_>>();
+    // globals are temporaries for the whole program, frame slots for their function
+    let (mut reads, mut tests) = (BTreeMap::new(), BTreeMap::new());
+    functions.iter().for_each(|(_, pieces)| usage(pieces, &mut reads, &mut tests));
+    let mut arguments = BTreeMap::new();
+    let functions = functions
+        .into_iter()
+        .map(|(function, mut pieces)| {
+            let (mut frame_reads, mut frame_tests) = (BTreeMap::new(), BTreeMap::new());
+            usage(&pieces, &mut frame_reads, &mut frame_tests);
+            let params = function.frame.map_or(0, |frame| read_first(&pieces, frame));
+            fold_conditions(&mut pieces, |slot| match (slot, function.frame) {
+                (Slot::Frame(offset), Some(frame)) => offset < 0 && offset + frame > params as i64 && frame_reads.get(&slot) == frame_tests.get(&slot),
+                (Slot::Frame(_), None) => false,
+                _ => reads.get(&slot) == tests.get(&slot)
+            });
+            for stmt in pieces.iter().flat_map(|piece| &piece.stmts) {
+                if let Stmt::Call(Callee::Function(target), args) = stmt {
+                    let count = arguments.entry(*target).or_insert(0);
+                    *count = args.len().max(*count);
+                }
+            }
+            (function, pieces)
+        })
+        .collect::<Vec<_>>();
+    // callers can pass arguments set before earlier calls, read_first finds them
+    let params = functions
+        .iter()
+        .map(|(function, pieces)| {
+            let callers = arguments.get(&function.entry).copied().unwrap_or(0);
+            (function.entry, function.frame.map_or(callers, |frame| callers.max(read_first(pieces, frame))))
+        })
+        .collect::<BTreeMap<_, _>>();
+    functions
+        .into_iter()
+        .map(|(function, mut pieces)| {
+            for stmt in pieces.iter_mut().flat_map(|piece| &mut piece.stmts) {
+                if let Stmt::Call(Callee::Function(target), args) = stmt {
+                    for k in args.len() + 1..=params.get(target).copied().unwrap_or(0) {
+                        args.push(Expr::Slot(Slot::Frame(k as i64)));
+                    }
+                }
+            }
+            Decompiled { entry: function.entry, frame: function.frame, params: params[&function.entry], body: structure(&pieces) }
+        })
+        .collect()
+}
+
+// Arguments of a function, the frame slots read before being written
+fn read_first(pieces: &[Piece], frame: i64) -> usize {
+    let mut written = BTreeSet::new();
+    let mut params = 0;
+    for stmt in pieces.iter().flat_map(|piece| &piece.stmts) {
+        for slot in stmt.reads() {
+            if let Slot::Frame(offset) = slot {
+                if offset > -frame && offset < 0 && !written.contains(&offset) {
+                    params = params.max((offset + frame) as usize);
+                }
+            }
+        }
+        if let Some(Slot::Frame(offset)) = stmt.writes() {
+            written.insert(offset);
+        }
+    }
+    params
+}
+
+impl Decompiled {
+    pub fn name(entry: usize) -> String {
+        if entry == 0 { "main".to_string() } else { format!("f{}", entry) }
+    }
+
+    fn slot(&self, slot: Slot) -> String {
+        match (slot, self.frame) {
+            (Slot::Global(address), _) => format!("g{}", address),
+            (Slot::Operand(address), _) => format!("p{}", address),
+            (Slot::Frame(offset), Some(frame)) if offset > -frame && offset < 0 => {
+                let index = (offset + frame) as usize;
+                if index <= self.params { format!("arg{}", index) } else { format!("local{}", index - self.params) }
+            },
+            (Slot::Frame(offset), _) if offset > 0 => format!("out{}", offset),
+            (Slot::Frame(offset), _) => format!("frame[{}]", offset)
+        }
+    }
+
+    fn expr(&self, expr: &Expr) -> String {
+        self.operand(expr, 0)
+    }
+
+    // Parenthesized when its operator binds less than the one it's an operand of
+    fn operand(&self, expr: &Expr, outer: u8) -> String {
+        match expr {
+            Expr::Const(value) => value.to_string(),
+            Expr::Slot(slot) => self.slot(*slot),
+            Expr::Mem(address) => format!("mem[{}]", self.expr(address)),
+            Expr::Frame(offset) => format!("frame[{}]", self.expr(offset)),
+            Expr::Input => "input()".to_string(),
+            Expr::Binary(op, left, right) => {
+                let (symbol, precedence) = match op {
+                    Op::Add => ("+", 2),
+                    Op::Mul => ("*", 3),
+                    Op::Lt => ("<", 1),
+                    Op::Ge => (">=", 1),
+                    Op::Eq => ("==", 1),
+                    Op::Ne => ("!=", 1)
+                };
+                let text = match (op, right.as_ref()) {
+                    (Op::Add, Expr::Const(value)) if *value < 0 && *value != i64::MIN =>
+                        format!("{} - {}", self.operand(left, precedence), -value),
+                    _ => format!("{} {} {}", self.operand(left, precedence), symbol, self.operand(right, precedence + 1))
+                };
+                if precedence < outer || (precedence == 1 && outer == 1) { format!("({})", text) } else { text }
+            }
+        }
+    }
+
+    fn block(&self, stmts: &[Stmt], depth: usize, text: &mut String) {
+        let indent = INDENT.repeat(depth);
+        for stmt in stmts {
+            match stmt {
+                Stmt::Assign(place, value) => writeln!(text, "{}{} = {}", indent, self.expr(place), self.expr(value)).unwrap(),
+                Stmt::Output(value) => writeln!(text, "{}output({})", indent, self.expr(value)).unwrap(),
+                Stmt::Call(callee, args) => {
+                    let callee = match callee {
+                        Callee::Function(entry) => Decompiled::name(*entry),
+                        Callee::Pointer(pointer) => format!("(*{})", self.expr(pointer))
+                    };
+                    let args = args.iter().map(|arg| self.expr(arg)).collect::<Vec<_>>().join(", ");
+                    writeln!(text, "{}{}({})", indent, callee, args).unwrap();
+                },
+                Stmt::Base(offset) => writeln!(text, "{}base += {}", indent, self.expr(offset)).unwrap(),
+                Stmt::If(cond, then, otherwise) => {
+                    writeln!(text, "{}if {} {{", indent, self.expr(cond)).unwrap();
+                    self.block(then, depth + 1, text);
+                    if !otherwise.is_empty() {
+                        writeln!(text, "{}}} else {{", indent).unwrap();
+                        self.block(otherwise, depth + 1, text);
+                    }
+                    writeln!(text, "{}}}", indent).unwrap();
+                },
+                Stmt::While(cond, body) => {
+                    writeln!(text, "{}while {} {{", indent, self.expr(cond)).unwrap();
+                    self.block(body, depth + 1, text);
+                    writeln!(text, "{}}}", indent).unwrap();
+                },
+                Stmt::DoWhile(body, cond) => {
+                    writeln!(text, "{}do {{", indent).unwrap();
+                    self.block(body, depth + 1, text);
+                    writeln!(text, "{}}} while {}", indent, self.expr(cond)).unwrap();
+                },
+                Stmt::Loop(body) => {
+                    writeln!(text, "{}loop {{", indent).unwrap();
+                    self.block(body, depth + 1, text);
+                    writeln!(text, "{}}}", indent).unwrap();
+                },
+                Stmt::Break => writeln!(text, "{}break", indent).unwrap(),
+                Stmt::Continue => writeln!(text, "{}continue", indent).unwrap(),
+                Stmt::Label(address) => writeln!(text, "{}L{}:", INDENT.repeat(depth.saturating_sub(1)), address).unwrap(),
+                Stmt::Goto(address) => writeln!(text, "{}goto L{}", indent, address).unwrap(),
+                Stmt::GotoComputed(target) => writeln!(text, "{}goto {}", indent, self.expr(target)).unwrap(),
+                Stmt::Unknown(address) => writeln!(text, "{}goto {} // not code yet, the program may write it", indent, address).unwrap(),
+                Stmt::Return => writeln!(text, "{}return", indent).unwrap(),
+                Stmt::Halt => writeln!(text, "{}halt", indent).unwrap()
+            }
+        }
+    }
+
+    pub fn pseudo_code(&self) -> String {
+        let params = (1..=self.params).map(|i| format!("arg{}", i)).collect::<Vec<_>>().join(", ");
+        let mut text = String::new();
+        match self.frame {
+            Some(frame) => writeln!(text, "// {}, frame {}", self.entry, frame).unwrap(),
+            None => writeln!(text, "// {}", self.entry).unwrap()
+        }
+        writeln!(text, "fn {}({}) {{", Decompiled::name(self.entry), params).unwrap();
+        self.block(&self.body, 1, &mut text);
+        writeln!(text, "}}").unwrap();
+        text
+    }
+}
+
+// Pseudo-code of all the functions of the program
+pub fn pseudo_code(codes: &[i64]) -> String {
+    decompile(codes).iter().map(|function| function.pseudo_code()).collect::<Vec<_>>().join("\n")
+}
+
+#[cfg(test)]
+const SUM: &str = "
+        ARB  #10
+        IN   @1
+        ADD  #ret, #0, @0
+        JZ   #0, #sum
+ret:    OUT  @1
+        HLT
+sum:    ARB  #4
+        ADD  #0, #0, @-2
+loop:   LT   #0, @-3, @-1
+        JZ   @-1, #end
+        ADD  @-2, @-3, @-2
+        ADD  @-3, #-1, @-3
+        JZ   #0, #loop
+end:    ADD  @-2, #0, @-3
+        ARB  #-4
+        JZ   #0, @0
+";
+
+#[test]
+fn test0_decompiler() {
+    let codes = crate::assembler::assemble(SUM).unwrap();
+    let functions = decompile(&codes);
+    assert_eq!(functions.len(), 2);
+    assert_eq!((functions[1].frame, functions[1].params), (Some(4), 1));
+    assert_eq!(functions[0].body, [
+        Stmt::Call(Callee::Function(14), vec![Expr::Input]),
+        Stmt::Output(Expr::Slot(Slot::Frame(1))),
+        Stmt::Halt
+    ]);
+    assert_eq!(pseudo_code(&codes), "\
+// 0, frame 10
+fn main() {
+    f14(input())
+    output(out1)
+    halt
+}
+
+// 14, frame 4
+fn f14(arg1) {
+    local1 = 0
+    while 0 < arg1 {
+        local1 = local1 + arg1
+        arg1 = arg1 - 1
+    }
+    arg1 = local1
+    return
+}
+");
+}
+
+#[test]
+fn test1_decompiler() {
+    // patched operand of an indexed read, then an if-else
+    let source = "
+        ADD  #table, g, read+1
+read:   ADD  0, #0, g
+        EQ   g, #7, t
+        JNZ  t, #seven
+        OUT  #0
+        JZ   #0, #done
+seven:  OUT  #1
+done:   HLT
+g:      .data 1
+t:      .data 0
+table:  .data 5, 7
+";
+    let codes = crate::assembler::assemble(source).unwrap();
+    assert_eq!(pseudo_code(&codes), "\
+// 0
+fn main() {
+    p5 = 25 + g23
+    g23 = mem[p5]
+    if g23 != 7 {
+        output(0)
+    } else {
+        output(1)
+    }
+    halt
+}
+");
+
+    // jumps out of the structure stay gotos
+    let codes = crate::assembler::assemble("
+        IN   x
+        JZ   x, #skip
+loop:   OUT  x
+        JNZ  x, #loop
+skip:   OUT  #2
+        JNZ  x, #loop
+        HLT
+x:      .data 0
+").unwrap();
+    let text = pseudo_code(&codes);
+    assert!(text.contains("L5:\n"), "{}", text);
+    assert!(text.contains("goto L5\n"), "{}", text);
+}
+
+#[test]
+fn test2_decompiler() {
+    // like day 5, the program adds the input to the word it goes on with
+    assert_eq!(pseudo_code(&[3,8,1,8,6,6,1100,1,0]), "\
+// 0
+fn main() {
+    g8 = input()
+    g6 = g8 + g6
+    goto 6 // not code yet, the program may write it
+}
+");
+}
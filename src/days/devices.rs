@@ -0,0 +1,304 @@
+//
+// I/O devices the IntCode VM talks to on opcodes 3 and 4
+//
+// A device can be a single type handling both directions, which is the natural
+// shape for puzzle logic reacting to outputs, or an (input, output) pair of halves
+//
+use std::collections::VecDeque;
+use std::fs::{ self, File };
+use std::io::{ self, BufWriter, Write };
+use std::path::Path;
+use std::sync::mpsc::{ Receiver, Sender, SyncSender };
+
+pub trait Device {
+    // None makes the program wait for input, it can be resumed later
+    fn input(&mut self) -> Option<i64>;
+    fn output(&mut self, value: i64);
+
+    // Takes back the last value read or written when the program steps back, devices
+    // which can't are left as they are
+    fn unread(&mut self, _value: i64) {}
+    fn unwrite(&mut self, _value: i64) {}
+}
+
+pub trait Input {
+    fn read(&mut self) -> Option<i64>;
+    fn unread(&mut self, _value: i64) {}
+}
+
+pub trait Output {
+    fn write(&mut self, value: i64);
+    fn unwrite(&mut self, _value: i64) {}
+}
+
+impl<I: Input, O: Output> Device for (I, O) {
+    fn input(&mut self) -> Option<i64> {
+        self.0.read()
+    }
+
+    fn output(&mut self, value: i64) {
+        self.1.write(value)
+    }
+
+    fn unread(&mut self, value: i64) {
+        self.0.unread(value)
+    }
+
+    fn unwrite(&mut self, value: i64) {
+        self.1.unwrite(value)
+    }
+}
+
+// Default device of IntCode, input and output queues
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Queues {
+    pub inputs: VecDeque<i64>,
+    pub outputs: VecDeque<i64>
+}
+
+impl Device for Queues {
+    fn input(&mut self) -> Option<i64> {
+        self.inputs.pop_front()
+    }
+
+    fn output(&mut self, value: i64) {
+        self.outputs.push_back(value)
+    }
+
+    fn unread(&mut self, value: i64) {
+        self.inputs.unread(value)
+    }
+
+    // outputs already taken by the caller stay taken
+    fn unwrite(&mut self, value: i64) {
+        self.outputs.unwrite(value)
+    }
+}
+
+impl Input for VecDeque<i64> {
+    fn read(&mut self) -> Option<i64> {
+        self.pop_front()
+    }
+
+    fn unread(&mut self, value: i64) {
+        self.push_front(value)
+    }
+}
+
+impl Output for VecDeque<i64> {
+    fn write(&mut self, value: i64) {
+        self.push_back(value)
+    }
+
+    fn unwrite(&mut self, value: i64) {
+        if self.back() == Some(&value) {
+            self.pop_back();
+        }
+    }
+}
+
+impl Output for Vec<i64> {
+    fn write(&mut self, value: i64) {
+        self.push(value)
+    }
+
+    fn unwrite(&mut self, value: i64) {
+        if self.last() == Some(&value) {
+            self.pop();
+        }
+    }
+}
+
+// Text fed one character at a time
+#[derive(Debug, Clone, Default)]
+pub struct AsciiInput {
+    chars: VecDeque<i64>
+}
+
+impl AsciiInput {
+    pub fn new(text: &str) -> Self {
+        let mut input = AsciiInput::default();
+        input.push_str(text);
+        input
+    }
+
+    pub fn push_str(&mut self, text: &str) {
+        self.chars.extend(text.chars().map(|c| c as i64))
+    }
+}
+
+impl Input for AsciiInput {
+    fn read(&mut self) -> Option<i64> {
+        self.chars.pop_front()
+    }
+}
+
+// Text collected from ASCII outputs, other values are kept apart as numbers
+#[derive(Debug, Clone, Default)]
+pub struct AsciiOutput {
+    pub text: String,
+    pub numbers: Vec<i64>
+}
+
+impl Output for AsciiOutput {
+    fn write(&mut self, value: i64) {
+        if (0..128).contains(&value) {
+            self.text.push(value as u8 as char);
+        } else {
+            self.numbers.push(value);
+        }
+    }
+}
+
+pub struct FnInput<F: FnMut() -> Option<i64>>(pub F);
+
+impl<F: FnMut() -> Option<i64>> Input for FnInput<F> {
+    fn read(&mut self) -> Option<i64> {
+        (self.0)()
+    }
+}
+
+pub struct FnOutput<F: FnMut(i64)>(pub F);
+
+impl<F: FnMut(i64)> Output for FnOutput<F> {
+    fn write(&mut self, value: i64) {
+        (self.0)(value)
+    }
+}
+
+// Waits for input when the channel is empty or disconnected
+impl Input for Receiver<i64> {
+    fn read(&mut self) -> Option<i64> {
+        self.try_recv().ok()
+    }
+}
+
+// Blocks the thread until a value arrives, waits for input only when disconnected
+pub struct BlockingReceiver(pub Receiver<i64>);
+
+impl Input for BlockingReceiver {
+    fn read(&mut self) -> Option<i64> {
+        self.0.recv().ok()
+    }
+}
+
+// Values sent to a disconnected receiver are dropped
+impl Output for Sender<i64> {
+    fn write(&mut self, value: i64) {
+        let _ = self.send(value);
+    }
+}
+
+impl Output for SyncSender<i64> {
+    fn write(&mut self, value: i64) {
+        let _ = self.send(value);
+    }
+}
+
+// Values separated by commas or whitespace, read when the device is opened
+#[derive(Debug, Clone, Default)]
+pub struct FileInput {
+    values: VecDeque<i64>
+}
+
+impl FileInput {
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let values = fs::read_to_string(path)?
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse::<i64>().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", s, e))))
+            .collect::<io::Result<VecDeque<_>>>()?;
+        Ok(FileInput { values })
+    }
+}
+
+impl Input for FileInput {
+    fn read(&mut self) -> Option<i64> {
+        self.values.pop_front()
+    }
+}
+
+// One value per line, the first error is kept and reported by finish
+pub struct FileOutput {
+    writer: BufWriter<File>,
+    error: Option<io::Error>
+}
+
+impl FileOutput {
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(FileOutput { writer: BufWriter::new(File::create(path)?), error: None })
+    }
+
+    pub fn finish(mut self) -> io::Result<()> {
+        match self.error.take() {
+            Some(error) => Err(error),
+            None => self.writer.flush()
+        }
+    }
+}
+
+impl Output for FileOutput {
+    fn write(&mut self, value: i64) {
+        if self.error.is_none() {
+            if let Err(error) = writeln!(self.writer, "{}", value) {
+                self.error = Some(error);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+const ECHO_UNTIL_ZERO: [i64; 11] = [3,10,4,10,1005,10,0,99,0,0,0];
+
+#[test]
+fn test0_devices() {
+    use crate::intcode::IntCode;
+
+    let mut program = IntCode::with_device(ECHO_UNTIL_ZERO.to_vec(), (AsciiInput::new("Hi\0"), AsciiOutput::default()));
+    program.process().unwrap();
+    assert_eq!(program.device().1.text, "Hi\0");
+}
+
+#[test]
+fn test1_devices() {
+    use crate::intcode::{ IntCode, Status };
+
+    let mut values = vec![3, 2, 1];
+    let mut echoed = Vec::new();
+    let mut program = IntCode::with_device(ECHO_UNTIL_ZERO.to_vec(), (FnInput(|| values.pop()), FnOutput(|v| echoed.push(v))));
+    assert_eq!(program.process(), Ok(Status::Waiting));
+    drop(program);
+    assert_eq!(echoed, [1, 2, 3]);
+}
+
+#[test]
+fn test2_devices() {
+    use std::sync::mpsc::channel;
+    use crate::intcode::{ IntCode, Status };
+
+    let (input_sender, input_receiver) = channel();
+    let (output_sender, output_receiver) = channel();
+    let mut program = IntCode::with_device(ECHO_UNTIL_ZERO.to_vec(), (input_receiver, output_sender));
+    input_sender.send(7).unwrap();
+    assert_eq!(program.process(), Ok(Status::Waiting));
+    input_sender.send(0).unwrap();
+    assert_eq!(program.process(), Ok(Status::End));
+    assert_eq!(output_receiver.try_iter().collect::<Vec<_>>(), [7, 0]);
+}
+
+#[test]
+fn test3_devices() {
+    use crate::intcode::{ IntCode, Status };
+
+    let input_path = std::env::temp_dir().join("devices_test3_input.txt");
+    let output_path = std::env::temp_dir().join("devices_test3_output.txt");
+    fs::write(&input_path, "5, 6\n0").unwrap();
+    let input = FileInput::open(&input_path).unwrap();
+    let output = FileOutput::create(&output_path).unwrap();
+    let mut program = IntCode::with_device(ECHO_UNTIL_ZERO.to_vec(), (input, output));
+    assert_eq!(program.process(), Ok(Status::End));
+    program.into_device().1.finish().unwrap();
+    assert_eq!(fs::read_to_string(&output_path).unwrap(), "5\n6\n0\n");
+    fs::remove_file(&input_path).unwrap();
+    fs::remove_file(&output_path).unwrap();
+}
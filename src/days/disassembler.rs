@@ -0,0 +1,291 @@
+//
+// Disassembler for the IntCode instruction set
+//
+// Code is told apart from data following jumps from address 0, so the listing
+// can be parsed back into the very same comma separated program
+//
+use std::collections::BTreeSet;
+use std::convert::TryFrom;
+use std::fmt;
+use crate::intcode::{ Instruction, IntCodeError, Mode, Opcode };
+
+const DATA_PER_LINE: usize = 8;
+
+#[derive(Debug, PartialEq)]
+pub enum Line {
+    Code { address: usize, instruction: Instruction, params: Vec<i64> },
+    Data { address: usize, values: Vec<i64> }
+}
+
+impl Line {
+    // Number of memory cells covered by the line
+    pub fn size(&self) -> usize {
+        match self {
+            Line::Code { instruction, .. } => instruction.size(),
+            Line::Data { values, .. } => values.len()
+        }
+    }
+}
+
+impl fmt::Display for Line {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Line::Code { address, instruction, params } => {
+                let operands = params
+                    .iter()
+                    .zip(instruction.modes.iter())
+                    .map(|(param, mode)| format!("{}{}", mode.prefix(), param))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let line = format!("{:>5}: {:<4} {}", address, instruction.opcode.mnemonic(), operands);
+                f.write_str(line.trim_end())
+            },
+            Line::Data { address, values } => {
+                let values = values.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(", ");
+                write!(f, "{:>5}: DATA {}", address, values)
+            }
+        }
+    }
+}
+
+pub fn disassemble(codes: &[i64]) -> Vec<Line> {
+    let starts = reachable(codes);
+    let mut lines = Vec::new();
+    let mut values = Vec::new();
+    let mut address = 0;
+
+    fn flush(lines: &mut Vec<Line>, values: &mut Vec<i64>, address: usize) {
+        if !values.is_empty() {
+            lines.push(Line::Data { address: address - values.len(), values: std::mem::take(values) });
+        }
+    }
+
+    while address < codes.len() {
+        match decode_at(codes, address).filter(|_| starts.contains(&address)) {
+            Some(instruction) => {
+                flush(&mut lines, &mut values, address);
+                let params = codes[address + 1..address + instruction.size()].to_vec();
+                lines.push(Line::Code { address, instruction, params });
+                address += instruction.size();
+            },
+            None => {
+                values.push(codes[address]);
+                address += 1;
+                if values.len() == DATA_PER_LINE || starts.contains(&address) {
+                    flush(&mut lines, &mut values, address);
+                }
+            }
+        }
+    }
+    flush(&mut lines, &mut values, address);
+    lines
+}
+
+// Single line at the given address, as data when it is not a complete instruction
+pub fn line_at(codes: &[i64], address: usize) -> Line {
+    line_of(|address| codes.get(address).copied(), address)
+}
+
+// Same reading the memory word by word, None past its end, so a running program
+// doesn't have to be copied
+pub fn line_of(word: impl Fn(usize) -> Option<i64>, address: usize) -> Line {
+    match decode_with(&word, address) {
+        Some(instruction) => {
+            let params = (address + 1..address + instruction.size()).map(|a| word(a).unwrap_or(0)).collect();
+            Line::Code { address, instruction, params }
+        },
+        None => Line::Data { address, values: vec![word(address).unwrap_or(0)] }
+    }
+}
+
+pub fn listing(codes: &[i64]) -> String {
+    disassemble(codes)
+        .iter()
+        .map(|line| format!("{}\n", line))
+        .collect()
+}
+
+pub fn parse_listing(listing: &str) -> Result<Vec<i64>, IntCodeError> {
+    let mut codes = Vec::new();
+    let wrong_token = |token: &str| IntCodeError::WrongToken {
+        token: token.to_string(),
+        offset: token.as_ptr() as usize - listing.as_ptr() as usize
+    };
+
+    for line in listing.lines().filter(|l| !l.trim().is_empty()) {
+        let (address, rest) = line.split_at(line.find(':').ok_or_else(|| wrong_token(line))?);
+        if address.trim().parse::<usize>().ok() != Some(codes.len()) {
+            return Err(wrong_token(address.trim()));
+        }
+        let rest = rest[1..].trim();
+        let (mnemonic, operands) = rest.split_at(rest.find(' ').unwrap_or(rest.len()));
+        let operands = operands
+            .split(',')
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>();
+
+        if mnemonic == "DATA" {
+            for value in operands {
+                codes.push(value.parse::<i64>().map_err(|_| wrong_token(value))?);
+            }
+        } else {
+            let opcode = Opcode::from_mnemonic(mnemonic).ok_or_else(|| wrong_token(mnemonic))?;
+            if operands.len() != opcode.params() {
+                return Err(wrong_token(rest));
+            }
+            let mut modes = [Mode::Position; 3];
+            let mut params = Vec::new();
+            for (operand, mode) in operands.into_iter().zip(modes.iter_mut()) {
+                let (prefix, value) = match operand.chars().next() {
+                    Some('#') => (Mode::Immediate, &operand[1..]),
+                    Some('@') => (Mode::Relative, &operand[1..]),
+                    _ => (Mode::Position, operand)
+                };
+                *mode = prefix;
+                params.push(value.parse::<i64>().map_err(|_| wrong_token(operand))?);
+            }
+            codes.push(Instruction::new(opcode, modes).encode());
+            codes.extend(params);
+        }
+    }
+    Ok(codes)
+}
+
+// Addresses of the instructions reachable from address 0
+pub fn reachable(codes: &[i64]) -> BTreeSet<usize> {
+    let mut starts = BTreeSet::new();
+    let mut pending = vec![0];
+    while let Some(address) = pending.pop() {
+        if starts.contains(&address) {
+            continue;
+        }
+        if let Some(instruction) = decode_at(codes, address) {
+            starts.insert(address);
+            pending.extend(successors(codes, address, &instruction));
+        }
+    }
+    starts
+}
+
+// Only words that encode back to themselves are instructions, anything else
+// would not survive a round trip through the listing
+pub fn decode_at(codes: &[i64], address: usize) -> Option<Instruction> {
+    decode_with(&|address| codes.get(address).copied(), address)
+}
+
+fn decode_with(word: &impl Fn(usize) -> Option<i64>, address: usize) -> Option<Instruction> {
+    let code = word(address)?;
+    Instruction::decode(code)
+        .filter(|instruction| instruction.encode() == code)
+        .filter(|instruction| word(address + instruction.size() - 1).is_some())
+}
+
+fn successors(codes: &[i64], address: usize, instruction: &Instruction) -> Vec<usize> {
+    let params = &codes[address + 1..address + instruction.size()];
+    let modes = instruction.modes;
+    let next = address + instruction.size();
+    let targets = match instruction.opcode {
+        Opcode::Hlt => vec![],
+        Opcode::Jnz | Opcode::Jz => {
+            let target = match modes[1] {
+                Mode::Immediate => Some(params[1]),
+                Mode::Position => usize::try_from(params[1]).ok().and_then(|p| codes.get(p).copied()),
+                Mode::Relative => None
+            };
+            let taken = if modes[0] == Mode::Immediate {
+                Some((params[0] != 0) == (instruction.opcode == Opcode::Jnz))
+            } else {
+                None
+            };
+            match taken {
+                Some(true) => target.into_iter().collect(),
+                Some(false) => vec![next as i64],
+                None => target.into_iter().chain(Some(next as i64)).collect()
+            }
+        },
+        // Return addresses are pushed on the relative base stack as constants before calls
+        Opcode::Add | Opcode::Mul if modes[0] == Mode::Immediate && modes[1] == Mode::Immediate && modes[2] == Mode::Relative => {
+            let value = if instruction.opcode == Opcode::Add {
+                params[0].checked_add(params[1])
+            } else {
+                params[0].checked_mul(params[1])
+            };
+            value.into_iter().chain(Some(next as i64)).collect()
+        },
+        _ => vec![next as i64]
+    };
+    targets
+        .into_iter()
+        .filter_map(|t| usize::try_from(t).ok())
+        .filter(|&t| t < codes.len())
+        .collect()
+}
+
+#[test]
+fn test0_listing() {
+    let codes = [109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99];
+    assert_eq!(listing(&codes), "    \
+            0: ARB  #1\n    \
+            2: OUT  @-1\n    \
+            4: ADD  100, #1, 100\n    \
+            8: EQ   100, #16, 101\n   \
+           12: JZ   101, #0\n   \
+           15: HLT\n");
+}
+
+#[test]
+fn test1_listing() {
+    let codes = [1102,34915192,34915192,7,4,7,99,0];
+    assert_eq!(listing(&codes), "    \
+            0: MUL  #34915192, #34915192, 7\n    \
+            4: OUT  7\n    \
+            6: HLT\n    \
+            7: DATA 0\n");
+}
+
+#[test]
+fn test2_listing() {
+    // call at 2 pushes return address 11 on the stack, function at 12 returns through it
+    let codes = [109,20,21101,11,0,0,1105,1,12,4,-1,99,204,0,2106,0,0,5,7];
+    assert_eq!(listing(&codes), "    \
+            0: ARB  #20\n    \
+            2: ADD  #11, #0, @0\n    \
+            6: JNZ  #1, #12\n    \
+            9: DATA 4, -1\n   \
+           11: HLT\n   \
+           12: OUT  @0\n   \
+           14: JZ   #0, @0\n   \
+           17: DATA 5, 7\n");
+}
+
+#[test]
+fn test0_line_at() {
+    // the ADD at 2 is cut short by the end of the memory
+    let codes = [109,20,21101,11];
+    let program = crate::intcode::IntCode::new(codes.to_vec());
+    for address in 0..6 {
+        assert_eq!(line_of(|a| program.word(a), address), line_at(&codes, address));
+    }
+    assert_eq!(line_at(&codes, 0).to_string(), "    0: ARB  #20");
+    assert_eq!(line_at(&codes, 2).to_string(), "    2: DATA 21101");
+    assert_eq!(line_at(&codes, 5).to_string(), "    5: DATA 0");
+}
+
+#[test]
+fn test0_parse_listing() {
+    for codes in [
+        vec![109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99],
+        vec![1102,34915192,34915192,7,4,7,99,0],
+        vec![3,21,1008,21,8,20,1005,20,22,107,8,21,20,1006,20,31,1106,0,36,98,0,0,1002,21,125,20,4,20,1105,1,46,104,999,1105,1,46,1101,1000,1,20,4,20,1105,1,46,98,99],
+        vec![10099,1,2,3,4,5,6,7,8,9,10,11,12,13,14,15,16,17,18]
+    ] {
+        assert_eq!(parse_listing(&listing(&codes)), Ok(codes));
+    }
+}
+
+#[test]
+fn test1_parse_listing() {
+    assert_eq!(parse_listing("0: ADD #1, 2\n"), Err(IntCodeError::WrongToken { token: "ADD #1, 2".to_string(), offset: 3 }));
+    assert_eq!(parse_listing("0: HLT\n2: HLT\n"), Err(IntCodeError::WrongToken { token: "2".to_string(), offset: 7 }));
+}
@@ -0,0 +1,88 @@
+//
+// Undo log of IntCode programs, for reverse execution
+//
+// In record mode, every executed instruction logs what it changes: the position and the
+// relative base before it, the old value of the address it writes and the length of the
+// memory before it grows, along with the value it reads from or writes to its device.
+// Stepping back applies the last entry in reverse. The log is bounded, the oldest
+// entries are dropped when it's full, so the program can only go back that many steps
+//
+use std::collections::VecDeque;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Io {
+    Input(i64),
+    Output(i64)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Undo {
+    pub pos: usize,
+    pub base: i64,
+    // address written and its value before
+    pub write: Option<(usize, i64)>,
+    // length of the memory before
+    pub len: usize,
+    pub io: Option<Io>
+}
+
+#[derive(Debug, Clone)]
+pub struct History {
+    log: VecDeque<Undo>,
+    capacity: usize,
+    dropped: usize
+}
+
+impl History {
+    pub fn new(capacity: usize) -> Self {
+        History { log: VecDeque::new(), capacity: capacity.max(1), dropped: 0 }
+    }
+
+    // Steps the program can go back
+    pub fn len(&self) -> usize {
+        self.log.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.log.is_empty()
+    }
+
+    // Steps recorded but forgotten since, the log being full
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    pub fn last(&self) -> Option<&Undo> {
+        self.log.back()
+    }
+
+    pub(crate) fn push(&mut self, undo: Undo) {
+        if self.log.len() == self.capacity {
+            self.log.pop_front();
+            self.dropped += 1;
+        }
+        self.log.push_back(undo);
+    }
+
+    pub(crate) fn pop(&mut self) -> Option<Undo> {
+        self.log.pop_back()
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.dropped += self.log.len();
+        self.log.clear();
+    }
+}
+
+#[test]
+fn test0_history() {
+    let undo = |pos: usize| Undo { pos, base: 0, write: None, len: 10, io: None };
+    let mut history = History::new(2);
+    assert!(history.is_empty());
+    (0..3).for_each(|pos| history.push(undo(pos)));
+    assert_eq!((history.len(), history.dropped()), (2, 1));
+    assert_eq!(history.last(), Some(&undo(2)));
+    assert_eq!(history.pop(), Some(undo(2)));
+    assert_eq!(history.pop(), Some(undo(1)));
+    assert_eq!(history.pop(), None);
+}
@@ -1,176 +1,874 @@
 //
 // This IntCode is the final evolution of days 2, 5, 7, 9
 //
-// It is used in days 9, 11, 13, 15, 17, 19, 21, 23, 25
+// It is used in days 2, 5, 7, 9, 11, 13, 15, 17, 19, 21, 23, 25, limited to the
+// instruction set of their puzzle in days 2, 5 and 7
+//
+// Inputs and outputs go through a device, input and output queues by default
+//
+// Limits bound the instructions a program executes, the words its memory grows by and
+// the values it writes, from the moment they are set. A program reaching one stops before
+// the instruction going over, with the status of the limit, and can go on with new limits.
+// Instructions run by compiled code are not counted
 //
 use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+use crate::devices::{ Device, Queues };
+use crate::history::{ History, Io, Undo };
+use crate::session::{ Entry, Session };
+use crate::tracer::{ Record, Tracer };
+
+// Memory is split in pages shared between snapshots and copied on first write
+const PAGE_SIZE: usize = 256;
+
+// Memory addresses used by an instruction, immediate parameters excluded
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Accesses {
+    pub reads: Vec<usize>,
+    pub write: Option<usize>
+}
+
+#[derive(Clone, Default)]
+struct Memory {
+    pages: Vec<Arc<[i64; PAGE_SIZE]>>,
+    len: usize
+}
+
+impl Memory {
+    fn new(codes: Vec<i64>) -> Self {
+        let pages = codes
+            .chunks(PAGE_SIZE)
+            .map(|chunk| {
+                let mut page = [0; PAGE_SIZE];
+                page[..chunk.len()].copy_from_slice(chunk);
+                Arc::new(page)
+            })
+            .collect();
+        Memory { pages, len: codes.len() }
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn get(&self, pos: usize) -> i64 {
+        if pos >= self.len { 0 } else { self.pages[pos / PAGE_SIZE][pos % PAGE_SIZE] }
+    }
+
+    fn set(&mut self, pos: usize, val: i64) {
+        if pos >= self.len {
+            let zero = Arc::new([0; PAGE_SIZE]);
+            self.pages.resize(pos / PAGE_SIZE + 1, zero);
+            self.len = pos + 1;
+        }
+        Arc::make_mut(&mut self.pages[pos / PAGE_SIZE])[pos % PAGE_SIZE] = val;
+    }
+
+    // Shrinks back after growing, the words past the length must read as zeros
+    fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+        self.pages.truncate(len.div_ceil(PAGE_SIZE));
+        // the last page is partly past the length, unless it's a full one
+        if let Some(page) = self.pages.get_mut(len / PAGE_SIZE) {
+            Arc::make_mut(page)[len % PAGE_SIZE..].fill(0);
+        }
+        self.len = len;
+    }
+
+    fn to_vec(&self) -> Vec<i64> {
+        self.pages.iter().flat_map(|page| page.iter()).take(self.len).copied().collect()
+    }
+}
 
 #[derive(Default)]
-pub struct IntCode {
-    inputs: VecDeque<i64>,
-    outputs: VecDeque<i64>,
-    codes: Vec<i64>,
+pub struct IntCode<D = Queues> {
+    device: D,
+    codes: Memory,
     pos: usize,
-    base: usize
+    base: i64,
+    level: Level,
+    tracer: Option<Tracer>,
+    history: Option<History>,
+    session: Option<Session>,
+    // instructions executed and values written
+    cycles: u64,
+    written: u64,
+    bounds: Bounds
+}
+
+// Budgets of a program, unlimited when None
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Limits {
+    pub cycles: Option<u64>,
+    // words past the end of the memory
+    pub memory: Option<usize>,
+    pub outputs: Option<u64>
 }
 
-#[derive(PartialEq)]
+// Limits as the counts they stop the program at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct Bounds {
+    cycles: Option<u64>,
+    len: Option<usize>,
+    written: Option<u64>
+}
+
+// Machine state, cheap to take because memory pages are shared with the running program
+#[derive(Clone)]
+pub struct Snapshot<D = Queues> {
+    device: D,
+    codes: Memory,
+    pos: usize,
+    base: i64,
+    // restoring rewinds the recorded session too, it's left to the instructions executed since
+    cycles: u64,
+    written: u64,
+    session: Option<Session>
+}
+
+// Instruction sets of the puzzles the VM went through, later ones include the earlier
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Level {
+    // ADD, MUL and HLT with position mode parameters
+    Day2,
+    // inputs, outputs, jumps, comparisons and immediate mode
+    Day5,
+    // relative base and relative mode
+    #[default]
+    Day9
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Status {
     Running,
     Waiting,
-    End
+    End,
+    // limits reached, the instruction at the position is not executed
+    CycleLimit,
+    MemoryLimit,
+    OutputLimit
 }
 
-impl IntCode {
-    pub fn new(codes: Vec<i64>) -> Self {
-        IntCode { codes, ..Default::default() }
+#[derive(Debug, Clone, PartialEq)]
+pub enum IntCodeError {
+    WrongOpcode { pos: usize, instruction: i64, base: i64 },
+    WrongParameterMode { pos: usize, instruction: i64, param: usize, base: i64 },
+    NegativeAddress { pos: usize, instruction: i64, param: usize, base: i64, address: i64 },
+    Overflow { pos: usize, instruction: i64, base: i64 },
+    WrongToken { token: String, offset: usize }
+}
+
+impl fmt::Display for IntCodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IntCodeError::WrongOpcode { pos, instruction, base } =>
+                write!(f, "wrong opcode {} at position {} (relative base {})", instruction, pos, base),
+            IntCodeError::WrongParameterMode { pos, instruction, param, base } =>
+                write!(f, "wrong parameter mode for parameter {} of opcode {} at position {} (relative base {})", param, instruction, pos, base),
+            IntCodeError::NegativeAddress { pos, instruction, param, base, address } =>
+                write!(f, "negative address {} for parameter {} of opcode {} at position {} (relative base {})", address, param, instruction, pos, base),
+            IntCodeError::Overflow { pos, instruction, base } =>
+                write!(f, "arithmetic overflow in opcode {} at position {} (relative base {})", instruction, pos, base),
+            IntCodeError::WrongToken { token, offset } =>
+                write!(f, "wrong token {:?} at offset {}", token, offset)
+        }
     }
+}
 
-    pub fn init_code(&mut self, d: i64) {
-        self.codes[0] = d;
+impl Error for IntCodeError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Opcode {
+    Add,
+    Mul,
+    In,
+    Out,
+    Jnz,
+    Jz,
+    Lt,
+    Eq,
+    Arb,
+    Hlt
+}
+
+impl Opcode {
+    pub const ALL: [Opcode; 10] = [
+        Opcode::Add, Opcode::Mul, Opcode::In, Opcode::Out, Opcode::Jnz,
+        Opcode::Jz, Opcode::Lt, Opcode::Eq, Opcode::Arb, Opcode::Hlt
+    ];
+
+    pub fn from_code(code: i64) -> Option<Self> {
+        Opcode::ALL.iter().copied().find(|op| op.code() == code)
+    }
+
+    pub fn from_mnemonic(mnemonic: &str) -> Option<Self> {
+        Opcode::ALL.iter().copied().find(|op| op.mnemonic().eq_ignore_ascii_case(mnemonic))
+    }
+
+    pub fn code(self) -> i64 {
+        match self {
+            Opcode::Add => 1,
+            Opcode::Mul => 2,
+            Opcode::In => 3,
+            Opcode::Out => 4,
+            Opcode::Jnz => 5,
+            Opcode::Jz => 6,
+            Opcode::Lt => 7,
+            Opcode::Eq => 8,
+            Opcode::Arb => 9,
+            Opcode::Hlt => 99
+        }
+    }
+
+    pub fn mnemonic(self) -> &'static str {
+        match self {
+            Opcode::Add => "ADD",
+            Opcode::Mul => "MUL",
+            Opcode::In => "IN",
+            Opcode::Out => "OUT",
+            Opcode::Jnz => "JNZ",
+            Opcode::Jz => "JZ",
+            Opcode::Lt => "LT",
+            Opcode::Eq => "EQ",
+            Opcode::Arb => "ARB",
+            Opcode::Hlt => "HLT"
+        }
+    }
+
+    // number of parameters following the instruction
+    pub fn params(self) -> usize {
+        match self {
+            Opcode::Add | Opcode::Mul | Opcode::Lt | Opcode::Eq => 3,
+            Opcode::Jnz | Opcode::Jz => 2,
+            Opcode::In | Opcode::Out | Opcode::Arb => 1,
+            Opcode::Hlt => 0
+        }
+    }
+
+    // index of the parameter the instruction writes to, if any
+    pub fn output_param(self) -> Option<usize> {
+        match self {
+            Opcode::Add | Opcode::Mul | Opcode::Lt | Opcode::Eq => Some(3),
+            Opcode::In => Some(1),
+            _ => None
+        }
+    }
+
+    pub fn level(self) -> Level {
+        match self {
+            Opcode::Add | Opcode::Mul | Opcode::Hlt => Level::Day2,
+            Opcode::Arb => Level::Day9,
+            _ => Level::Day5
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mode {
+    Position,
+    Immediate,
+    Relative
+}
+
+impl Mode {
+    pub fn from_code(code: i64) -> Option<Self> {
+        match code {
+            0 => Some(Mode::Position),
+            1 => Some(Mode::Immediate),
+            2 => Some(Mode::Relative),
+            _ => None
+        }
+    }
+
+    pub fn code(self) -> i64 {
+        match self {
+            Mode::Position => 0,
+            Mode::Immediate => 1,
+            Mode::Relative => 2
+        }
+    }
+
+    pub fn prefix(self) -> &'static str {
+        match self {
+            Mode::Position => "",
+            Mode::Immediate => "#",
+            Mode::Relative => "@"
+        }
+    }
+
+    pub fn level(self) -> Level {
+        match self {
+            Mode::Position => Level::Day2,
+            Mode::Immediate => Level::Day5,
+            Mode::Relative => Level::Day9
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Instruction {
+    pub opcode: Opcode,
+    pub modes: [Mode; 3]
+}
+
+impl Instruction {
+    pub fn new(opcode: Opcode, modes: [Mode; 3]) -> Self {
+        Instruction { opcode, modes }
+    }
+
+    // Decodes the parameter modes the opcode actually uses, other digits are ignored like the VM does
+    pub fn decode(word: i64) -> Option<Self> {
+        let opcode = Opcode::from_code(word % 100)?;
+        let mut modes = [Mode::Position; 3];
+        for (param, mode) in modes.iter_mut().enumerate().take(opcode.params()) {
+            *mode = Mode::from_code((word / 10_i64.pow(param as u32 + 2)) % 10)?;
+        }
+        Some(Instruction { opcode, modes })
+    }
+
+    pub fn encode(&self) -> i64 {
+        self.modes
+            .iter()
+            .take(self.opcode.params())
+            .enumerate()
+            .fold(self.opcode.code(), |word, (param, mode)| word + mode.code() * 10_i64.pow(param as u32 + 2))
+    }
+
+    // Size in memory, instruction word included
+    pub fn size(&self) -> usize {
+        self.opcode.params() + 1
+    }
+}
+
+impl IntCode {
+    pub fn new(codes: Vec<i64>) -> Self {
+        IntCode::with_device(codes, Queues::default())
     }
 
     pub fn read(&mut self) -> Vec<i64> {
-        self.outputs.drain(..).collect()
+        self.device.outputs.drain(..).collect()
     }
 
     pub fn read_string(&mut self) -> String {
-        self.outputs.drain(..).map(|d| d as u8 as char).collect()
+        self.device.outputs.drain(..).map(|d| d as u8 as char).collect()
     }
 
     pub fn read_one(&mut self) -> Option<i64> {
-        self.outputs.pop_front()
+        self.device.outputs.pop_front()
     }
 
     pub fn no_output(&self) -> bool {
-        self.outputs.is_empty()
+        self.device.outputs.is_empty()
     }
 
     pub fn write(&mut self, input: &[i64]) {
-        self.inputs.extend(input)
+        self.device.inputs.extend(input)
     }
 
     pub fn write_string(&mut self, input: &str) {
-        self.inputs.extend(input.chars().map(|c| c as i64))
+        self.device.inputs.extend(input.chars().map(|c| c as i64))
     }
 
     pub fn write_one(&mut self, input: i64) {
-        self.inputs.push_back(input)
+        self.device.inputs.push_back(input)
     }
 
     pub fn no_input(&self) -> bool {
-        self.inputs.is_empty()
+        self.device.inputs.is_empty()
+    }
+
+    pub fn inputs(&self) -> &VecDeque<i64> {
+        &self.device.inputs
+    }
+
+    pub fn outputs(&self) -> &VecDeque<i64> {
+        &self.device.outputs
+    }
+}
+
+impl<D: Device + Clone> IntCode<D> {
+    pub fn snapshot(&self) -> Snapshot<D> {
+        Snapshot {
+            device: self.device.clone(),
+            codes: self.codes.clone(),
+            pos: self.pos,
+            base: self.base,
+            cycles: self.cycles,
+            written: self.written,
+            session: self.session.clone()
+        }
+    }
+
+    pub fn restore(&mut self, snapshot: &Snapshot<D>) {
+        self.device = snapshot.device.clone();
+        self.codes = snapshot.codes.clone();
+        self.pos = snapshot.pos;
+        self.base = snapshot.base;
+        self.cycles = snapshot.cycles;
+        self.written = snapshot.written;
+        if let Some(session) = &mut self.session {
+            *session = snapshot.session.clone().unwrap_or_default();
+        }
+        // the steps recorded lead to another state
+        if let Some(history) = &mut self.history {
+            history.clear();
+        }
+    }
+}
+
+impl<D: Device> IntCode<D> {
+    pub fn with_device(codes: Vec<i64>, device: D) -> Self {
+        IntCode { device, codes: Memory::new(codes), pos: 0, base: 0, level: Level::default(), tracer: None, history: None, session: None, cycles: 0, written: 0, bounds: Bounds::default() }
+    }
+
+    // Opcodes and modes beyond the level are reported as wrong
+    pub fn set_level(&mut self, level: Level) {
+        self.level = level;
+    }
+
+    pub fn device(&self) -> &D {
+        &self.device
+    }
+
+    pub fn device_mut(&mut self) -> &mut D {
+        &mut self.device
+    }
+
+    pub fn into_device(self) -> D {
+        self.device
+    }
+
+    pub fn init_code(&mut self, d: i64) {
+        self.codes.set(0, d);
+    }
+
+    pub fn set_tracer(&mut self, tracer: Tracer) {
+        self.tracer = Some(tracer);
+    }
+
+    pub fn take_tracer(&mut self) -> Option<Tracer> {
+        self.tracer.take()
+    }
+
+    // Records the steps from now on, to be able to go back, see history
+    pub fn set_history(&mut self, history: History) {
+        self.history = Some(history);
+    }
+
+    pub fn take_history(&mut self) -> Option<History> {
+        self.history.take()
+    }
+
+    pub fn history(&self) -> Option<&History> {
+        self.history.as_ref()
+    }
+
+    // Records the values read and written from now on, see session
+    pub fn set_session(&mut self, session: Session) {
+        self.session = Some(session);
+    }
+
+    pub fn take_session(&mut self) -> Option<Session> {
+        self.session.take()
+    }
+
+    pub fn cycles(&self) -> u64 {
+        self.cycles
+    }
+
+    // Replaces the limits, their budgets start from the current state
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.bounds = Bounds {
+            cycles: limits.cycles.map(|cycles| self.cycles.saturating_add(cycles)),
+            len: limits.memory.map(|words| self.codes.len().saturating_add(words)),
+            written: limits.outputs.map(|outputs| self.written.saturating_add(outputs))
+        };
+    }
+
+    fn grows_past_limit(&self, address: usize) -> bool {
+        address >= self.codes.len() && self.bounds.len.is_some_and(|len| address >= len)
+    }
+
+    // Undoes the last recorded step, None when there's none left. Memory written by poke
+    // since isn't restored, devices take back the values they support to, and the session
+    // forgets the value the step read or wrote
+    pub fn step_back(&mut self) -> Option<Undo> {
+        let undo = self.history.as_mut()?.pop()?;
+        if let Some((address, old)) = undo.write {
+            self.set(address, old);
+        }
+        self.codes.truncate(undo.len);
+        self.pos = undo.pos;
+        self.base = undo.base;
+        self.cycles -= 1;
+        let cycles = self.cycles;
+        if let Some(session) = &mut self.session {
+            while session.entries.last().is_some_and(|entry| entry.cycle >= cycles) {
+                session.entries.pop();
+            }
+        }
+        match undo.io {
+            Some(Io::Input(value)) => self.device.unread(value),
+            Some(Io::Output(value)) => {
+                self.written -= 1;
+                self.device.unwrite(value)
+            },
+            None => ()
+        }
+        Some(undo)
+    }
+
+    // Steps back until the instruction at the address is about to be executed again,
+    // returns the number of steps, None if the history runs out before
+    pub fn run_back_to(&mut self, address: usize) -> Option<usize> {
+        let mut steps = 0;
+        loop {
+            self.step_back()?;
+            steps += 1;
+            if self.pos == address {
+                return Some(steps);
+            }
+        }
+    }
+
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    pub fn base(&self) -> i64 {
+        self.base
+    }
+
+    pub fn memory(&self) -> Vec<i64> {
+        self.codes.to_vec()
+    }
+
+    pub fn peek(&self, address: usize) -> i64 {
+        self.get(address)
+    }
+
+    // Word at the address without copying the memory, None past its end
+    pub fn word(&self, address: usize) -> Option<i64> {
+        (address < self.codes.len()).then(|| self.get(address))
+    }
+
+    pub fn poke(&mut self, address: usize, value: i64) {
+        self.set(address, value)
+    }
+
+    // Moves the program to another position and relative base, as compiled code does
+    pub fn set_registers(&mut self, pos: usize, base: i64) {
+        self.pos = pos;
+        self.base = base;
+    }
+
+    // Addresses the instruction at the current position is going to read and write
+    pub fn accesses(&self) -> Result<Accesses, IntCodeError> {
+        let mode = self.get(self.pos);
+        let opcode = Opcode::from_code(mode % 100)
+            .ok_or(IntCodeError::WrongOpcode { pos: self.pos, instruction: mode, base: self.base })?;
+        let mut accesses = Accesses::default();
+        for param in 1..=opcode.params() {
+            let address = self.get_position(mode, param)?;
+            if Some(param) == opcode.output_param() {
+                accesses.write = Some(address);
+            } else if (mode / 10_i64.pow(param as u32 + 1)) % 10 != 1 {
+                accesses.reads.push(address);
+            }
+        }
+        Ok(accesses)
     }
 
     fn get(&self, pos: usize) -> i64 {
-        if pos >= self.codes.len() { 0 } else { self.codes[pos] }
+        self.codes.get(pos)
     }
 
     fn set(&mut self, pos: usize, val: i64) {
-        if pos >= self.codes.len() {
-            let extended_size = pos - self.codes.len() + 1;
-            self.codes.extend(vec![0; extended_size]);
-        }
-        self.codes[pos] = val;
+        self.codes.set(pos, val)
     }
 
-    fn get_position(&self, mode: i64, param: usize) -> usize {
+    fn get_position(&self, mode: i64, param: usize) -> Result<usize, IntCodeError> {
         let param_mode = (mode / 10_i64.pow(param as u32 + 1)) % 10;
-        match param_mode {
-            0 => self.get(self.pos + param) as usize,
-            1 => self.pos + param,
-            2 => (self.base as i64 + self.get(self.pos + param)) as usize,
-            _ => panic!("wrong parameter mode {} at position {} for opcode {}", param_mode, self.pos, mode)
+        let address = match param_mode {
+            0 => self.get(self.pos + param),
+            1 => return Ok(self.pos + param),
+            2 => self.base.checked_add(self.get(self.pos + param)).ok_or_else(|| self.overflow(mode))?,
+            _ => return Err(IntCodeError::WrongParameterMode { pos: self.pos, instruction: mode, param, base: self.base })
+        };
+        self.to_address(mode, param, address)
+    }
+
+    fn get_param(&self, mode: i64, param: usize) -> Result<i64, IntCodeError> {
+        Ok(self.get(self.get_position(mode, param)?))
+    }
+
+    fn to_address(&self, mode: i64, param: usize, address: i64) -> Result<usize, IntCodeError> {
+        if address < 0 {
+            Err(IntCodeError::NegativeAddress { pos: self.pos, instruction: mode, param, base: self.base, address })
+        } else {
+            Ok(address as usize)
         }
     }
 
-    fn get_param(&self, mode: i64, param: usize) -> i64 {
-        self.get(self.get_position(mode, param))
+    fn check_level(&self, mode: i64) -> Result<(), IntCodeError> {
+        let opcode = match Opcode::from_code(mode % 100) {
+            Some(opcode) if opcode.level() > self.level =>
+                return Err(IntCodeError::WrongOpcode { pos: self.pos, instruction: mode, base: self.base }),
+            Some(opcode) => opcode,
+            None => return Ok(())
+        };
+        for param in 1..=opcode.params() {
+            let param_mode = Mode::from_code((mode / 10_i64.pow(param as u32 + 1)) % 10);
+            if param_mode.is_some_and(|m| m.level() > self.level) {
+                return Err(IntCodeError::WrongParameterMode { pos: self.pos, instruction: mode, param, base: self.base });
+            }
+        }
+        Ok(())
     }
 
-    pub fn process(&mut self) -> Status {
+    fn overflow(&self, mode: i64) -> IntCodeError {
+        IntCodeError::Overflow { pos: self.pos, instruction: mode, base: self.base }
+    }
+
+    pub fn process(&mut self) -> Result<Status, IntCodeError> {
         self.process_interruptable(|| false)
     }
 
-    pub fn process_interruptable(&mut self, mut interrupt: impl FnMut() -> bool) -> Status {
+    pub fn process_interruptable(&mut self, mut interrupt: impl FnMut() -> bool) -> Result<Status, IntCodeError> {
         while self.pos < self.codes.len() {
             if interrupt() {
-                return Status::Running
+                return Ok(Status::Running)
+            }
+            match self.step()? {
+                Status::Running => (),
+                status => return Ok(status)
+            }
+        }
+        Ok(Status::End)
+    }
+
+    // Executes a single instruction, Running means that it has been executed
+    pub fn step(&mut self) -> Result<Status, IntCodeError> {
+        if self.tracer.is_none() && self.history.is_none() && self.session.is_none() {
+            return self.execute();
+        }
+        let (pos, base, len, cycle) = (self.pos, self.base, self.codes.len(), self.cycles);
+        let instruction = self.get(pos);
+        let write = self.accesses().ok().and_then(|a| a.write);
+        let old = write.map(|address| (address, self.get(address)));
+        let output = if instruction % 100 == 4 { self.get_param(instruction, 1).ok() } else { None };
+        let result = self.execute();
+        if let Some(mut tracer) = self.tracer.take() {
+            if result == Ok(Status::Running) || (result == Ok(Status::End) && pos < self.codes.len()) {
+                let params = Opcode::from_code(instruction % 100)
+                    .map_or(Vec::new(), |op| (1..=op.params()).map(|p| self.get(pos + p)).collect());
+                let write = write.map(|address| (address, self.get(address)));
+                tracer.record(&Record { pos, instruction, params, write, next: self.pos });
             }
-            let mode = self.get(self.pos);
-            let opcode = mode % 100;
-            match opcode {
-                1 => {
-                    let res = self.get_position(mode,3);
-                    let val1 = self.get_param(mode, 1);
-                    let val2 = self.get_param(mode, 2);
-                    self.set(res,val1 + val2);
-                    self.pos += 4;
-                },
-                2 => {
-                    let res = self.get_position(mode,3);
-                    let val1 = self.get_param(mode, 1);
-                    let val2 = self.get_param(mode, 2);
-                    self.set(res, val1 * val2);
-                    self.pos += 4;
-                },
-                3 => {
-                    let res = self.get_position(mode,1);
-                    if self.no_input() {
-                        return Status::Waiting;
-                    } else {
-                        let input = self.inputs.pop_front().unwrap();
+            self.tracer = Some(tracer);
+        }
+        if result == Ok(Status::Running) {
+            let io = match instruction % 100 {
+                3 => write.map(|address| Io::Input(self.get(address))),
+                4 => output.map(Io::Output),
+                _ => None
+            };
+            if let Some(history) = &mut self.history {
+                history.push(Undo { pos, base, write: old, len, io });
+            }
+            if let (Some(session), Some(io)) = (&mut self.session, io) {
+                session.entries.push(Entry { cycle, io });
+            }
+        }
+        result
+    }
+
+    fn execute(&mut self) -> Result<Status, IntCodeError> {
+        if self.pos >= self.codes.len() {
+            return Ok(Status::End)
+        }
+        if self.bounds.cycles.is_some_and(|cycles| self.cycles >= cycles) {
+            return Ok(Status::CycleLimit)
+        }
+        let mode = self.get(self.pos);
+        if self.level < Level::Day9 {
+            self.check_level(mode)?;
+        }
+        let opcode = mode % 100;
+        match opcode {
+            1 => {
+                let res = self.get_position(mode,3)?;
+                if self.grows_past_limit(res) { return Ok(Status::MemoryLimit) }
+                let val1 = self.get_param(mode, 1)?;
+                let val2 = self.get_param(mode, 2)?;
+                self.set(res, val1.checked_add(val2).ok_or_else(|| self.overflow(mode))?);
+                self.pos += 4;
+            },
+            2 => {
+                let res = self.get_position(mode,3)?;
+                if self.grows_past_limit(res) { return Ok(Status::MemoryLimit) }
+                let val1 = self.get_param(mode, 1)?;
+                let val2 = self.get_param(mode, 2)?;
+                self.set(res, val1.checked_mul(val2).ok_or_else(|| self.overflow(mode))?);
+                self.pos += 4;
+            },
+            3 => {
+                let res = self.get_position(mode,1)?;
+                if self.grows_past_limit(res) { return Ok(Status::MemoryLimit) }
+                match self.device.input() {
+                    Some(input) => {
                         self.set(res, input);
                         self.pos += 2;
-                    }
-                },
-                4 => {
-                    self.outputs.push_back(self.get_param(mode, 1));
-                    self.pos += 2;
-                },
-                5 => {
-                    let val1 = self.get_param(mode, 1);
-                    let val2 = self.get_param(mode, 2);
-                    if val1 != 0 { self.pos = val2 as usize } else { self.pos += 3; };
-                },
-                6 => {
-                    let val1 = self.get_param(mode, 1);
-                    let val2 = self.get_param(mode, 2);
-                    if val1 == 0 { self.pos = val2 as usize } else { self.pos += 3; };
-                },
-                7 => {
-                    let res = self.get_position(mode, 3);
-                    let val1 = self.get_param(mode, 1);
-                    let val2 = self.get_param(mode, 2);
-                    self.set(res, if val1 < val2 { 1 } else { 0 });
-                    self.pos += 4;
-                },
-                8 => {
-                    let res = self.get_position(mode,3);
-                    let val1 = self.get_param(mode, 1);
-                    let val2 = self.get_param(mode, 2);
-                    self.set(res, if val1 == val2 { 1 } else { 0 });
-                    self.pos += 4;
-                },
-                9 => {
-                    self.base = (self.base as i64 + self.get_param(mode, 1)) as usize;
-                    self.pos += 2;
-                },
-                99 => break,
-                _ => panic!("wrong opcode {} at position {}", self.get(self.pos), self.pos)
+                    },
+                    None => return Ok(Status::Waiting)
+                }
+            },
+            4 => {
+                if self.bounds.written.is_some_and(|written| self.written >= written) {
+                    return Ok(Status::OutputLimit)
+                }
+                let output = self.get_param(mode, 1)?;
+                self.device.output(output);
+                self.written += 1;
+                self.pos += 2;
+            },
+            5 => {
+                let val1 = self.get_param(mode, 1)?;
+                let val2 = self.get_param(mode, 2)?;
+                if val1 != 0 { self.pos = self.to_address(mode, 2, val2)? } else { self.pos += 3; };
+            },
+            6 => {
+                let val1 = self.get_param(mode, 1)?;
+                let val2 = self.get_param(mode, 2)?;
+                if val1 == 0 { self.pos = self.to_address(mode, 2, val2)? } else { self.pos += 3; };
+            },
+            7 => {
+                let res = self.get_position(mode, 3)?;
+                if self.grows_past_limit(res) { return Ok(Status::MemoryLimit) }
+                let val1 = self.get_param(mode, 1)?;
+                let val2 = self.get_param(mode, 2)?;
+                self.set(res, if val1 < val2 { 1 } else { 0 });
+                self.pos += 4;
+            },
+            8 => {
+                let res = self.get_position(mode,3)?;
+                if self.grows_past_limit(res) { return Ok(Status::MemoryLimit) }
+                let val1 = self.get_param(mode, 1)?;
+                let val2 = self.get_param(mode, 2)?;
+                self.set(res, if val1 == val2 { 1 } else { 0 });
+                self.pos += 4;
+            },
+            9 => {
+                let val1 = self.get_param(mode, 1)?;
+                self.base = self.base.checked_add(val1).ok_or_else(|| self.overflow(mode))?;
+                self.pos += 2;
+            },
+            99 => return Ok(Status::End),
+            _ => return Err(IntCodeError::WrongOpcode { pos: self.pos, instruction: mode, base: self.base })
+        }
+        self.cycles += 1;
+        Ok(Status::Running)
+    }
+}
+
+// Text format with one field per line, lists are comma separated like the puzzle inputs
+impl Snapshot {
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Snapshot> {
+        fs::read_to_string(path)?
+            .parse()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+impl fmt::Display for Snapshot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fn join(values: impl Iterator<Item = i64>) -> String {
+            values.map(|v| v.to_string()).collect::<Vec<_>>().join(",")
+        }
+        writeln!(f, "pos {}", self.pos)?;
+        writeln!(f, "base {}", self.base)?;
+        writeln!(f, "inputs {}", join(self.device.inputs.iter().copied()))?;
+        writeln!(f, "outputs {}", join(self.device.outputs.iter().copied()))?;
+        writeln!(f, "memory {}", join(self.codes.to_vec().into_iter()))
+    }
+}
+
+impl std::str::FromStr for Snapshot {
+    type Err = IntCodeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut snapshot = IntCode::new(Vec::new()).snapshot();
+        let mut offset = 0;
+        for line in s.lines() {
+            let (key, value) = line.split_at(line.find(' ').unwrap_or(line.len()));
+            let value = value.trim_start();
+            let value_offset = offset + line.len() - value.len();
+            let wrong_token = || IntCodeError::WrongToken { token: line.to_string(), offset };
+            let codes = || if value.trim().is_empty() {
+                Ok(Vec::new())
+            } else {
+                extract_codes(value).map_err(|e| match e {
+                    IntCodeError::WrongToken { token, offset } => IntCodeError::WrongToken { token, offset: value_offset + offset },
+                    e => e
+                })
+            };
+            match key {
+                "pos" => snapshot.pos = value.trim().parse().map_err(|_| wrong_token())?,
+                "base" => snapshot.base = value.trim().parse().map_err(|_| wrong_token())?,
+                "inputs" => snapshot.device.inputs = codes()?.into(),
+                "outputs" => snapshot.device.outputs = codes()?.into(),
+                "memory" => snapshot.codes = Memory::new(codes()?),
+                _ => return Err(wrong_token())
             }
+            offset += line.len() + 1;
         }
-        Status::End
+        Ok(snapshot)
     }
 }
 
-pub fn extract_codes(contents: &str) -> Vec<i64> {
+impl<D: Device + Clone> From<&IntCode<D>> for Snapshot<D> {
+    fn from(program: &IntCode<D>) -> Self {
+        program.snapshot()
+    }
+}
+
+impl<D: Device + Clone + Default> From<&Snapshot<D>> for IntCode<D> {
+    fn from(snapshot: &Snapshot<D>) -> Self {
+        let mut program = IntCode::default();
+        program.restore(snapshot);
+        program
+    }
+}
+
+pub fn extract_codes(contents: &str) -> Result<Vec<i64>, IntCodeError> {
+    let mut offset = 0;
     contents
         .split(',')
-        .map(|s| s.parse::<i64>().unwrap())
+        .map(|s| {
+            let code = s.trim().parse::<i64>()
+                .map_err(|_| IntCodeError::WrongToken { token: s.to_string(), offset });
+            offset += s.len() + 1;
+            code
+        })
         .collect()
 }
 
+// Parse step of the days running a program, which is all their parsing
+pub fn parse(contents: &str) {
+    let _ = std::hint::black_box(extract_codes(contents));
+}
+
 pub fn interrupt_after(cycles: usize) -> impl FnMut() -> bool {
     let mut elapsed = 0;
     move || {
@@ -178,3 +876,139 @@ pub fn interrupt_after(cycles: usize) -> impl FnMut() -> bool {
         elapsed >= cycles
     }
 }
+
+#[test]
+fn test0_wrong_opcode() {
+    let mut program = IntCode::new(extract_codes("1101,2,3,5,42,0,99").unwrap());
+    assert_eq!(program.process(), Err(IntCodeError::WrongOpcode { pos: 4, instruction: 42, base: 0 }));
+}
+
+#[test]
+fn test1_wrong_parameter_mode() {
+    let mut program = IntCode::new(extract_codes("109,3,1301,1,2,0,99").unwrap());
+    assert_eq!(program.process(), Err(IntCodeError::WrongParameterMode { pos: 2, instruction: 1301, param: 1, base: 3 }));
+}
+
+#[test]
+fn test2_negative_address() {
+    let mut program = IntCode::new(extract_codes("109,-5,204,2,99").unwrap());
+    assert_eq!(program.process(), Err(IntCodeError::NegativeAddress { pos: 2, instruction: 204, param: 1, base: -5, address: -3 }));
+}
+
+#[test]
+fn test3_negative_jump() {
+    let mut program = IntCode::new(extract_codes("1105,1,-1,99").unwrap());
+    assert_eq!(program.process(), Err(IntCodeError::NegativeAddress { pos: 0, instruction: 1105, param: 2, base: 0, address: -1 }));
+}
+
+#[test]
+fn test4_overflow() {
+    let mut program = IntCode::new(extract_codes("1102,4611686018427387904,2,0,99").unwrap());
+    assert_eq!(program.process(), Err(IntCodeError::Overflow { pos: 0, instruction: 1102, base: 0 }));
+}
+
+#[test]
+fn test5_wrong_token() {
+    assert_eq!(extract_codes("1,2,x3,99"), Err(IntCodeError::WrongToken { token: "x3".to_string(), offset: 4 }));
+}
+
+#[test]
+fn test6_level() {
+    let mut program = IntCode::new(extract_codes("1,0,0,0,3,0,99").unwrap());
+    program.set_level(Level::Day2);
+    assert_eq!(program.process(), Err(IntCodeError::WrongOpcode { pos: 4, instruction: 3, base: 0 }));
+    let mut program = IntCode::new(extract_codes("1002,4,3,4,33").unwrap());
+    program.set_level(Level::Day2);
+    assert_eq!(program.process(), Err(IntCodeError::WrongParameterMode { pos: 0, instruction: 1002, param: 2, base: 0 }));
+    let mut program = IntCode::new(extract_codes("1002,4,3,4,33").unwrap());
+    program.set_level(Level::Day5);
+    assert_eq!(program.process(), Ok(Status::End));
+}
+
+#[test]
+fn test0_snapshot() {
+    let mut program = IntCode::new(extract_codes("3,9,8,9,10,9,4,9,99,-1,8").unwrap());
+    let start = program.snapshot();
+    program.write_one(8);
+    program.process().unwrap();
+    assert_eq!(program.read(), [1]);
+    program.restore(&start);
+    program.write_one(7);
+    program.process().unwrap();
+    assert_eq!(program.read(), [0]);
+    assert_eq!(IntCode::from(&start).memory(), extract_codes("3,9,8,9,10,9,4,9,99,-1,8").unwrap());
+}
+
+#[test]
+fn test1_snapshot() {
+    let mut program = IntCode::new(extract_codes("109,-1,3,1000,204,1001,99").unwrap());
+    program.write(&[5, 6]);
+    program.step().unwrap();
+    program.step().unwrap();
+    let snapshot = program.snapshot();
+    let text = snapshot.to_string();
+    assert_eq!(text, format!("pos 4\nbase -1\ninputs 6\noutputs \nmemory 109,-1,3,1000,204,1001,99{},5\n", ",0".repeat(993)));
+    let path = std::env::temp_dir().join("intcode_test1_snapshot.txt");
+    snapshot.save(&path).unwrap();
+    let mut restored = IntCode::from(&Snapshot::load(&path).unwrap());
+    fs::remove_file(&path).unwrap();
+    assert_eq!(restored.process(), Ok(Status::End));
+    assert_eq!(restored.read(), [5]);
+    assert_eq!(restored.inputs(), &[6]);
+}
+
+#[test]
+fn test2_snapshot() {
+    assert_eq!("pos 1\nbase 0\nmemory 1,x".parse::<Snapshot>().err(), Some(IntCodeError::WrongToken { token: "x".to_string(), offset: 22 }));
+    assert_eq!("pos 1\nsize 2".parse::<Snapshot>().err(), Some(IntCodeError::WrongToken { token: "size 2".to_string(), offset: 6 }));
+}
+
+#[test]
+fn test0_step_back() {
+    // writes past the end of the memory, then outputs what it read
+    let mut program = IntCode::new(vec![3,20,1001,20,5,300,4,300,99]);
+    program.set_history(History::new(100));
+    program.write_one(7);
+    assert_eq!(program.process(), Ok(Status::End));
+    assert_eq!(program.history().map(|history| history.len()), Some(3));
+    assert_eq!(program.outputs(), &[12]);
+
+    let undo = program.step_back().unwrap();
+    assert_eq!((undo.pos, undo.io), (6, Some(Io::Output(12))));
+    assert!(program.outputs().is_empty());
+    assert_eq!(program.step_back().map(|undo| undo.write), Some(Some((300, 0))));
+    assert_eq!(program.memory().len(), 21);
+    assert_eq!(program.peek(300), 0);
+    assert_eq!(program.run_back_to(0), Some(1));
+    assert_eq!((program.peek(20), program.inputs().front()), (0, Some(&7)));
+    assert_eq!(program.step_back(), None);
+    assert_eq!(program.run_back_to(0), None);
+
+    assert_eq!(program.process(), Ok(Status::End));
+    assert_eq!(program.read(), [12]);
+    assert_eq!(program.memory().len(), 301);
+}
+
+#[test]
+fn test0_limits() {
+    // writes past the end of the memory, then outputs what it read
+    let mut program = IntCode::new(vec![3,20,1001,20,5,300,4,300,99]);
+    program.set_limits(Limits { cycles: Some(1), ..Limits::default() });
+    assert_eq!(program.process(), Ok(Status::Waiting));
+    program.write_one(7);
+    assert_eq!(program.process(), Ok(Status::CycleLimit));
+    assert_eq!((program.pos, program.cycles()), (2, 1));
+
+    program.set_limits(Limits { memory: Some(12), ..Limits::default() });
+    assert_eq!(program.process(), Ok(Status::MemoryLimit));
+    assert_eq!((program.pos, program.memory().len()), (2, 21));
+
+    program.set_limits(Limits { memory: Some(280), outputs: Some(0), ..Limits::default() });
+    assert_eq!(program.process(), Ok(Status::OutputLimit));
+    assert_eq!((program.pos, program.peek(300)), (6, 12));
+    assert!(program.outputs().is_empty());
+
+    program.set_limits(Limits::default());
+    assert_eq!(program.process(), Ok(Status::End));
+    assert_eq!(program.read(), [12]);
+}
@@ -0,0 +1,324 @@
+//
+// Interpreters of days 2, 5 and 7 as they were before moving to the IntCode VM
+//
+// They are only kept to check that the VM, limited to the instruction set of each day,
+// behaves the same on every program of those days
+//
+use std::fs;
+use permutohedron::Heap;
+use crate::intcode::{ IntCode, Level, Status, extract_codes };
+
+fn day02_interpreter(mut codes: Vec<usize>) -> Vec<usize> {
+    let mut pos = 0;
+    while pos < codes.len() - 4 {
+        let res = codes[pos + 3];
+        let op1 = codes[pos + 1];
+        let op2 = codes[pos + 2];
+        match codes[pos] {
+            1 => codes[res] = codes[op1] + codes[op2],
+            2 => codes[res] = codes[op1] * codes[op2],
+            99 => break,
+            _ => panic!("wrong opcode {} at position {}", codes[pos], pos)
+        }
+        pos += 4;
+    }
+    codes
+}
+
+fn day05_interpreter(mut codes: Vec<i32>, input: i32) -> (Vec<i32>, i32) {
+    fn get_param_value(mode: i32, param: usize, codes: &[i32], pos: usize) -> i32 {
+        let param_mode = (mode / 10_i32.pow(param as u32 + 1)) % 10;
+        match param_mode {
+            0 => {
+                let op = codes[pos + param] as usize;
+                codes[op]
+            },
+            1 => codes[pos + param],
+            _ => panic!("wrong parameter mode {} at position {} for opcode {}", param_mode, pos, mode)
+        }
+    }
+
+    let mut output = 0;
+    let mut pos = 0;
+
+    while pos < codes.len() {
+        let mode = codes[pos];
+        let opcode = mode % 100;
+        match opcode {
+            1 => {
+                let res = codes[pos + 3] as usize;
+                let val1 = get_param_value(mode, 1, &codes, pos);
+                let val2 = get_param_value(mode, 2, &codes, pos);
+                codes[res] = val1 + val2;
+                pos += 4;
+            },
+            2 => {
+                let res = codes[pos + 3] as usize;
+                let val1 = get_param_value(mode, 1, &codes, pos);
+                let val2 = get_param_value(mode, 2, &codes, pos);
+                codes[res] = val1 * val2;
+                pos += 4;
+            },
+            3 => {
+                let res = codes[pos + 1] as usize;
+                codes[res] = input;
+                pos += 2;
+            },
+            4 => {
+                output = get_param_value(mode, 1, &codes, pos);
+                pos += 2;
+            },
+            5 => {
+                let val1 = get_param_value(mode, 1, &codes, pos);
+                let val2 = get_param_value(mode, 2, &codes, pos);
+                if val1 != 0 { pos = val2 as usize } else { pos += 3; };
+            },
+            6 => {
+                let val1 = get_param_value(mode, 1, &codes, pos);
+                let val2 = get_param_value(mode, 2, &codes, pos);
+                if val1 == 0 { pos = val2 as usize } else { pos += 3; };
+            },
+            7 => {
+                let res = codes[pos + 3] as usize;
+                let val1 = get_param_value(mode, 1, &codes, pos);
+                let val2 = get_param_value(mode, 2, &codes, pos);
+                codes[res] = if val1 < val2 { 1 } else { 0 };
+                pos += 4;
+            },
+            8 => {
+                let res = codes[pos + 3] as usize;
+                let val1 = get_param_value(mode, 1, &codes, pos);
+                let val2 = get_param_value(mode, 2, &codes, pos);
+                codes[res] = if val1 == val2 { 1 } else { 0 };
+                pos += 4;
+            },
+            99 => break,
+            _ => panic!("wrong opcode {} at position {}", codes[pos], pos)
+        }
+    }
+    (codes, output)
+}
+
+fn extract_codes_i32(contents: &str) -> Vec<i32> {
+    contents
+        .split(',')
+        .map(|s| s.parse::<i32>().unwrap())
+        .collect()
+}
+
+#[derive(Default)]
+struct Ampli {
+    inputs: Vec<i32>,
+    outputs: Vec<i32>,
+    codes: Vec<i32>,
+    pos: usize
+}
+
+impl Ampli {
+    fn new(codes: Vec<i32>, inputs: Vec<i32>) -> Self {
+        Ampli { inputs, codes, ..Default::default() }
+    }
+
+    fn get_param_value(&self, mode: i32, param: usize) -> i32 {
+        let param_mode = (mode / 10_i32.pow(param as u32 + 1)) % 10;
+        match param_mode {
+            0 => {
+                let op = self.codes[self.pos + param] as usize;
+                self.codes[op]
+            },
+            1 => self.codes[self.pos + param],
+            _ => panic!("wrong parameter mode {} at position {} for opcode {}", param_mode, self.pos, mode)
+        }
+    }
+
+    fn interpreter(&mut self) -> usize {
+        while self.pos < self.codes.len() {
+            let mode = self.codes[self.pos];
+            let opcode = mode % 100;
+            match opcode {
+                1 => {
+                    let res = self.codes[self.pos + 3] as usize;
+                    let val1 = self.get_param_value(mode, 1);
+                    let val2 = self.get_param_value(mode, 2);
+                    self.codes[res] = val1 + val2;
+                    self.pos += 4;
+                },
+                2 => {
+                    let res = self.codes[self.pos + 3] as usize;
+                    let val1 = self.get_param_value(mode, 1);
+                    let val2 = self.get_param_value(mode, 2);
+                    self.codes[res] = val1 * val2;
+                    self.pos += 4;
+                },
+                3 => {
+                    let res = self.codes[self.pos + 1] as usize;
+                    if self.inputs.is_empty() {
+                        return 0;
+                    } else {
+                        self.codes[res] = self.inputs.remove(0);
+                        self.pos += 2;
+                    }
+                },
+                4 => {
+                    self.outputs.push(self.get_param_value(mode, 1));
+                    self.pos += 2;
+                },
+                5 => {
+                    let val1 = self.get_param_value(mode, 1);
+                    let val2 = self.get_param_value(mode, 2);
+                    if val1 != 0 { self.pos = val2 as usize } else { self.pos += 3; };
+                },
+                6 => {
+                    let val1 = self.get_param_value(mode, 1);
+                    let val2 = self.get_param_value(mode, 2);
+                    if val1 == 0 { self.pos = val2 as usize } else { self.pos += 3; };
+                },
+                7 => {
+                    let res = self.codes[self.pos + 3] as usize;
+                    let val1 = self.get_param_value(mode, 1);
+                    let val2 = self.get_param_value(mode, 2);
+                    self.codes[res] = if val1 < val2 { 1 } else { 0 };
+                    self.pos += 4;
+                },
+                8 => {
+                    let res = self.codes[self.pos + 3] as usize;
+                    let val1 = self.get_param_value(mode, 1);
+                    let val2 = self.get_param_value(mode, 2);
+                    self.codes[res] = if val1 == val2 { 1 } else { 0 };
+                    self.pos += 4;
+                },
+                99 => break,
+                _ => panic!("wrong opcode {} at position {}", self.codes[self.pos], self.pos)
+            }
+        }
+        1
+    }
+}
+
+const DAY02_PROGRAMS: [&str; 5] = [
+    "1,9,10,3,2,3,11,0,99,30,40,50",
+    "1,0,0,0,99",
+    "2,3,0,3,99",
+    "2,4,4,5,99,0",
+    "1,1,1,4,99,5,6,0,99"
+];
+
+const DAY05_PROGRAMS: [&str; 8] = [
+    "1002,4,3,4,33",
+    "3,9,8,9,10,9,4,9,99,-1,8",
+    "3,9,7,9,10,9,4,9,99,-1,8",
+    "3,3,1108,-1,8,3,4,3,99",
+    "3,3,1107,-1,8,3,4,3,99",
+    "3,12,6,12,15,1,13,14,13,4,13,99,-1,0,1,9",
+    "3,3,1105,-1,9,1101,0,0,12,4,12,99,1",
+    "3,21,1008,21,8,20,1005,20,22,107,8,21,20,1006,20,31,1106,0,36,98,0,0,1002,21,125,20,4,20,1105,1,46,104,999,1105,1,46,1101,1000,1,20,4,20,1105,1,46,98,99"
+];
+
+const DAY07_PROGRAMS: [&str; 5] = [
+    "3,15,3,16,1002,16,10,16,1,16,15,15,4,15,99,0,0",
+    "3,23,3,24,1002,24,10,24,1002,23,-1,23,101,5,23,23,1,24,23,23,4,23,99,0,0",
+    "3,31,3,32,1002,32,10,32,1001,31,-2,31,1007,31,0,33,1002,33,7,33,1,33,31,31,1,32,31,31,4,31,99,0,0,0",
+    "3,26,1001,26,-4,26,3,27,1002,27,2,27,1,27,26,27,4,27,1001,28,-1,28,1005,28,6,99,0,0,5",
+    "3,52,1001,52,-5,52,3,53,1,52,56,54,1007,54,5,55,1005,55,26,1001,54,-5,54,1105,1,12,1,53,54,53,1008,54,0,55,1001,55,1,55,2,53,55,53,4,53,1001,56,-1,56,1005,56,6,99,0,0,0,0,10"
+];
+
+fn input(day: &str) -> String {
+    fs::read_to_string(format!("./input/{}.txt", day))
+        .expect("Something went wrong reading the file")
+        .trim()
+        .to_string()
+}
+
+// Amplifiers in a feedback loop, which is a single pass for programs halting after their output
+fn legacy_amplifiers(codes: &[i32], phases: &[i32]) -> i32 {
+    let mut amplis = phases.iter().map(|p| Ampli::new(codes.to_owned(), vec![*p])).collect::<Vec<_>>();
+    let mut output = vec![0];
+    let mut completed = 0;
+    while completed < amplis.len() {
+        for ampli in amplis.iter_mut() {
+            ampli.inputs.append(&mut output);
+            completed += ampli.interpreter();
+            output = ampli.outputs.drain(..).collect();
+        }
+    }
+    output[0]
+}
+
+fn amplifiers(codes: &[i64], phases: &[i64]) -> i64 {
+    let mut amplis = phases
+        .iter()
+        .map(|p| {
+            let mut ampli = IntCode::new(codes.to_owned());
+            ampli.set_level(Level::Day5);
+            ampli.write_one(*p);
+            ampli
+        })
+        .collect::<Vec<_>>();
+    let mut output = vec![0];
+    let mut completed = 0;
+    while completed < amplis.len() {
+        for ampli in amplis.iter_mut() {
+            ampli.write(&output);
+            if ampli.process().unwrap() == Status::End {
+                completed += 1;
+            }
+            output = ampli.read();
+        }
+    }
+    output[0]
+}
+
+#[test]
+fn test0_day02() {
+    let mut programs = DAY02_PROGRAMS.iter().map(|p| extract_codes(p).unwrap()).collect::<Vec<_>>();
+    let codes = extract_codes(&input("day02")).unwrap();
+    for noun in 0..100 {
+        for verb in 0..100 {
+            let mut codes = codes.clone();
+            codes[1] = noun;
+            codes[2] = verb;
+            programs.push(codes);
+        }
+    }
+    for codes in programs {
+        let legacy = day02_interpreter(codes.iter().map(|&c| c as usize).collect());
+        let mut program = IntCode::new(codes.clone());
+        program.set_level(Level::Day2);
+        assert_eq!(program.process(), Ok(Status::End), "{:?}", codes);
+        assert_eq!(program.memory(), legacy.iter().map(|&c| c as i64).collect::<Vec<_>>(), "{:?}", codes);
+    }
+}
+
+#[test]
+fn test1_day05() {
+    let mut runs = DAY05_PROGRAMS.iter().flat_map(|p| (-10..=10).map(move |i| (p.to_string(), i))).collect::<Vec<_>>();
+    runs.push((input("day05"), 1));
+    runs.push((input("day05"), 5));
+    for (contents, input) in runs {
+        let (legacy_codes, legacy_output) = day05_interpreter(extract_codes_i32(&contents), input);
+        let mut program = IntCode::new(extract_codes(&contents).unwrap());
+        program.set_level(Level::Day5);
+        program.write_one(input as i64);
+        assert_eq!(program.process(), Ok(Status::End), "{} with {}", contents, input);
+        assert_eq!(program.memory(), legacy_codes.iter().map(|&c| c as i64).collect::<Vec<_>>(), "{} with {}", contents, input);
+        assert_eq!(program.read().last().copied().unwrap_or(0), legacy_output as i64, "{} with {}", contents, input);
+    }
+}
+
+#[test]
+fn test2_day07() {
+    let mut programs = DAY07_PROGRAMS.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+    programs.push(input("day07"));
+    for contents in programs {
+        let legacy_codes = extract_codes_i32(&contents);
+        let codes = extract_codes(&contents).unwrap();
+        for phases in &[[0, 1, 2, 3, 4], [5, 6, 7, 8, 9]] {
+            let mut phases = *phases;
+            let mut permutations = Heap::new(&mut phases);
+            while let Some(phases) = permutations.next_permutation() {
+                let legacy_phases = phases.iter().map(|&p| p as i32).collect::<Vec<_>>();
+                assert_eq!(amplifiers(&codes, phases), legacy_amplifiers(&legacy_codes, &legacy_phases) as i64, "{} with {:?}", contents, phases);
+            }
+        }
+    }
+}
@@ -1,4 +1,17 @@
 pub mod intcode;
+pub mod disassembler;
+pub mod assembler;
+pub mod debugger;
+pub mod tracer;
+pub mod history;
+pub mod session;
+pub mod devices;
+pub mod network;
+pub mod compiler;
+pub mod analysis;
+pub mod decompiler;
+#[cfg(test)]
+mod legacy;
 pub mod day01;
 pub mod day02;
 pub mod day03;
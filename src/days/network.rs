@@ -0,0 +1,347 @@
+//
+// Network of IntCode computers, the NICs of day 23
+//
+// Nodes are spread over worker threads and packets go through a channel per node.
+// The network runs in rounds: each node first collects the packets sent to it during
+// the previous round, ordered by sender, then runs until it waits for input. Barriers
+// separate the two phases, so results don't depend on thread scheduling and the
+// network is idle when a whole round goes by without any input or packet. A NAT that
+// panics stops the workers before its panic goes on
+//
+use std::collections::VecDeque;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::panic::{ self, AssertUnwindSafe };
+use std::sync::atomic::{ AtomicBool, AtomicUsize, Ordering };
+use std::sync::mpsc::{ channel, Receiver, Sender };
+use std::sync::{ Barrier, Mutex };
+use std::thread;
+use crate::devices::Device;
+use crate::intcode::{ IntCode, IntCodeError };
+
+pub const NAT: usize = 255;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Packet {
+    pub source: usize,
+    pub address: usize,
+    pub x: i64,
+    pub y: i64
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    Continue,
+    Send(Packet),
+    Stop
+}
+
+// Component listening at address 255
+pub trait Nat {
+    // Called between rounds for each packet sent to 255, in the order of their senders
+    fn receive(&mut self, packet: Packet) -> Action;
+    // Called after a round without traffic, the network is stuck if nothing is sent
+    fn idle(&mut self) -> Action;
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NetworkError {
+    Node { address: usize, error: IntCodeError },
+    Deadlock { rounds: usize }
+}
+
+impl fmt::Display for NetworkError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NetworkError::Node { address, error } =>
+                write!(f, "node {}: {}", address, error),
+            NetworkError::Deadlock { rounds } =>
+                write!(f, "network idle after {} rounds and the NAT sends nothing", rounds)
+        }
+    }
+}
+
+impl Error for NetworkError {}
+
+struct Nic<'a> {
+    address: usize,
+    inbox: Receiver<Packet>,
+    queue: VecDeque<i64>,
+    sending: Vec<i64>,
+    routes: &'a [Sender<Packet>],
+    nat: &'a Sender<Packet>,
+    // inputs pending and packets sent during the round, none when the node is idle
+    traffic: usize
+}
+
+impl Nic<'_> {
+    fn receive(&mut self) {
+        let mut packets = self.inbox.try_iter().collect::<Vec<_>>();
+        packets.sort_by_key(|p| p.source);
+        for packet in packets {
+            self.queue.extend(&[packet.x, packet.y]);
+        }
+        if self.queue.is_empty() {
+            self.queue.push_back(-1);
+        } else {
+            self.traffic += 1;
+        }
+    }
+}
+
+impl Device for Nic<'_> {
+    fn input(&mut self) -> Option<i64> {
+        self.queue.pop_front()
+    }
+
+    // Packets to unknown addresses are lost
+    fn output(&mut self, value: i64) {
+        self.sending.push(value);
+        if let [address, x, y] = self.sending[..] {
+            let route = match usize::try_from(address) {
+                Ok(NAT) => Some(self.nat),
+                Ok(address) => self.routes.get(address),
+                Err(_) => None
+            };
+            if let Some(route) = route {
+                let _ = route.send(Packet { source: self.address, address: address as usize, x, y });
+            }
+            self.traffic += 1;
+            self.sending.clear();
+        }
+    }
+}
+
+pub struct Network {
+    codes: Vec<i64>,
+    size: usize,
+    workers: usize
+}
+
+impl Network {
+    pub fn new(codes: Vec<i64>, size: usize) -> Self {
+        let workers = thread::available_parallelism().map_or(1, |n| n.get());
+        Network { codes, size, workers }
+    }
+
+    pub fn set_workers(&mut self, workers: usize) {
+        self.workers = workers.max(1);
+    }
+
+    // Boots every node with its address and runs until the NAT stops, returns the number of rounds.
+    // A network without nodes is idle from the start
+    pub fn run(&self, nat: &mut impl Nat) -> Result<usize, NetworkError> {
+        if self.size == 0 {
+            return Err(NetworkError::Deadlock { rounds: 0 });
+        }
+        let (routes, inboxes): (Vec<_>, Vec<_>) = (0..self.size).map(|_| channel()).unzip();
+        let (nat_route, nat_inbox) = channel();
+        let mut nodes = inboxes
+            .into_iter()
+            .enumerate()
+            .map(|(address, inbox)| {
+                let queue = vec![address as i64].into();
+                let nic = Nic { address, inbox, queue, sending: Vec::new(), routes: &routes, nat: &nat_route, traffic: 0 };
+                IntCode::with_device(self.codes.clone(), nic)
+            })
+            .collect::<Vec<_>>();
+
+        // fewer chunks than workers when the nodes don't split evenly
+        let workers = self.workers.min(self.size);
+        let chunks = nodes.chunks_mut(self.size.div_ceil(workers)).collect::<Vec<_>>();
+        let barrier = Barrier::new(chunks.len() + 1);
+        let stop = AtomicBool::new(false);
+        let traffic = AtomicUsize::new(0);
+        let failures = Mutex::new(Vec::new());
+
+        thread::scope(|scope| {
+            for chunk in chunks {
+                let (barrier, stop, traffic, failures) = (&barrier, &stop, &traffic, &failures);
+                scope.spawn(move || loop {
+                    barrier.wait();
+                    if stop.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    chunk.iter_mut().for_each(|node| node.device_mut().receive());
+                    barrier.wait();
+                    let mut sent = 0;
+                    for node in chunk.iter_mut() {
+                        if let Err(error) = node.process() {
+                            failures.lock().unwrap().push(NetworkError::Node { address: node.device().address, error });
+                        }
+                        sent += std::mem::take(&mut node.device_mut().traffic);
+                    }
+                    traffic.fetch_add(sent, Ordering::Relaxed);
+                    barrier.wait();
+                });
+            }
+
+            let mut rounds = 0;
+            let route = |packet: Packet| {
+                if let Some(route) = routes.get(packet.address) {
+                    let _ = route.send(Packet { source: NAT, ..packet });
+                }
+            };
+            let result = panic::catch_unwind(AssertUnwindSafe(|| loop {
+                barrier.wait();
+                barrier.wait();
+                barrier.wait();
+                rounds += 1;
+
+                {
+                    let mut failures = failures.lock().unwrap();
+                    if !failures.is_empty() {
+                        failures.sort_by_key(|e| match e { NetworkError::Node { address, .. } => *address, _ => 0 });
+                        break Err(failures.remove(0));
+                    }
+                }
+                let mut packets = nat_inbox.try_iter().collect::<Vec<_>>();
+                packets.sort_by_key(|p| p.source);
+                let mut action = Action::Continue;
+                for packet in packets {
+                    action = nat.receive(packet);
+                    match action {
+                        Action::Send(packet) => route(packet),
+                        Action::Stop => break,
+                        Action::Continue => ()
+                    }
+                }
+                if action == Action::Stop {
+                    break Ok(rounds);
+                }
+                if traffic.swap(0, Ordering::Relaxed) == 0 {
+                    match nat.idle() {
+                        Action::Send(packet) => route(packet),
+                        Action::Stop => break Ok(rounds),
+                        Action::Continue => break Err(NetworkError::Deadlock { rounds })
+                    }
+                }
+            }));
+            // the workers wait for the next round, in which they stop
+            stop.store(true, Ordering::Relaxed);
+            barrier.wait();
+            result.unwrap_or_else(|payload| panic::resume_unwind(payload))
+        })
+    }
+}
+
+// Each node increments x and passes the packet to the next one, skipping 255, the last one to the NAT
+#[cfg(test)]
+fn ring(size: usize) -> Vec<i64> {
+    crate::assembler::assemble(&format!("
+                IN   addr
+                JNZ  addr, #loop
+                OUT  #1
+                OUT  #1
+                OUT  #0
+        loop:   IN   x
+                EQ   x, #-1, tmp
+                JNZ  tmp, #loop
+                IN   y
+                ADD  addr, #1, next
+                EQ   next, #255, tmp
+                ADD  next, tmp, next
+                EQ   next, #{}, tmp
+                JZ   tmp, #send
+                ADD  #255, #0, next
+        send:   OUT  next
+                ADD  x, #1, x
+                OUT  x
+                OUT  y
+                JNZ  #1, #loop
+        addr:   .data 0
+        x:      .data 0
+        y:      .data 0
+        next:   .data 0
+        tmp:    .data 0
+    ", size)).unwrap()
+}
+
+#[cfg(test)]
+#[derive(Default)]
+struct Recorder {
+    packets: Vec<Packet>,
+    idle: usize
+}
+
+#[cfg(test)]
+impl Nat for Recorder {
+    fn receive(&mut self, packet: Packet) -> Action {
+        self.packets.push(packet);
+        Action::Continue
+    }
+
+    // wakes node 0 up once, then gives up
+    fn idle(&mut self) -> Action {
+        self.idle += 1;
+        match self.idle {
+            1 => Action::Send(Packet { source: NAT, address: 0, x: 0, y: 7 }),
+            _ => Action::Continue
+        }
+    }
+}
+
+#[test]
+fn test0_network() {
+    let mut network = Network::new(ring(1000), 1000);
+    let mut results = Vec::new();
+    for workers in [1, 7] {
+        network.set_workers(workers);
+        let mut nat = Recorder::default();
+        results.push((network.run(&mut nat), nat.packets));
+    }
+    assert_eq!(results[0], results[1]);
+    assert_eq!(results[0].1, [
+        Packet { source: 999, address: NAT, x: 999, y: 0 },
+        Packet { source: 999, address: NAT, x: 999, y: 7 }
+    ]);
+    assert_eq!(results[0].0, Err(NetworkError::Deadlock { rounds: 2000 }));
+}
+
+#[test]
+fn test1_network() {
+    let mut network = Network::new(vec![3,5,4,5,42], 3);
+    network.set_workers(2);
+    assert_eq!(network.run(&mut Recorder::default()), Err(NetworkError::Node {
+        address: 0,
+        error: IntCodeError::WrongOpcode { pos: 4, instruction: 42, base: 0 }
+    }));
+}
+
+#[test]
+fn test2_network() {
+    // workers left without nodes, or without a chunk of their own
+    let mut network = Network::new(ring(50), 50);
+    let mut results = Vec::new();
+    for workers in 1..=64 {
+        network.set_workers(workers);
+        let mut nat = Recorder::default();
+        results.push((network.run(&mut nat), nat.packets));
+    }
+    assert!(results.iter().all(|result| *result == results[0]));
+    assert_eq!(results[0].0, Err(NetworkError::Deadlock { rounds: 102 }));
+    assert_eq!(Network::new(ring(1), 0).run(&mut Recorder::default()), Err(NetworkError::Deadlock { rounds: 0 }));
+}
+
+#[cfg(test)]
+struct Panicking;
+
+#[cfg(test)]
+impl Nat for Panicking {
+    fn receive(&mut self, _: Packet) -> Action {
+        panic!("NAT failure")
+    }
+
+    fn idle(&mut self) -> Action {
+        Action::Send(Packet { source: NAT, address: 0, x: 0, y: 0 })
+    }
+}
+
+#[test]
+fn test3_network() {
+    let mut network = Network::new(ring(10), 10);
+    network.set_workers(3);
+    let result = panic::catch_unwind(AssertUnwindSafe(|| network.run(&mut Panicking)));
+    assert_eq!(result.unwrap_err().downcast_ref::<&str>(), Some(&"NAT failure"));
+}
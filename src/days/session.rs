@@ -0,0 +1,274 @@
+//
+// Sessions of IntCode programs: the values they read and write, to replay them
+//
+// A program records its session from the moment it's given one, each value read by IN or
+// written by OUT along with the cycle it happens at, the number of instructions executed
+// before. Restoring a snapshot rewinds the session along with the program. Sessions are
+// saved as text, with the words of the program to set before it runs first:
+//   set <address> <value>
+//   <cycle> in <value>
+//   <cycle> out <value>
+// Replaying feeds the recorded inputs to the program and checks it reads and writes the
+// same values at the same cycles, which gives regression tests of interactive programs
+// without the code driving them
+//
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+use crate::history::Io;
+use crate::intcode::{ IntCode, IntCodeError };
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Entry {
+    pub cycle: u64,
+    pub io: Io
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Session {
+    // words of the program set before it runs, like the coins of day 13
+    pub memory: Vec<(usize, i64)>,
+    pub entries: Vec<Entry>
+}
+
+#[derive(Debug, PartialEq)]
+pub struct SessionError {
+    pub line: usize,
+    pub message: String
+}
+
+impl fmt::Display for SessionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for SessionError {}
+
+#[derive(Debug, PartialEq)]
+pub enum ReplayError {
+    IntCode(IntCodeError),
+    // first entry that differs, None when one of the sessions is shorter
+    Mismatch { index: usize, expected: Option<Entry>, found: Option<Entry> }
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let entry = |entry: &Option<Entry>| entry.map_or("nothing".to_string(), |entry| entry.to_string());
+        match self {
+            ReplayError::IntCode(e) => write!(f, "{}", e),
+            ReplayError::Mismatch { index, expected, found } =>
+                write!(f, "entry {}: expected {}, found {}", index, entry(expected), entry(found))
+        }
+    }
+}
+
+impl Error for ReplayError {}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.io {
+            Io::Input(value) => write!(f, "{} in {}", self.cycle, value),
+            Io::Output(value) => write!(f, "{} out {}", self.cycle, value)
+        }
+    }
+}
+
+impl fmt::Display for Session {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (address, value) in &self.memory {
+            writeln!(f, "set {} {}", address, value)?;
+        }
+        for entry in &self.entries {
+            writeln!(f, "{}", entry)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Session {
+    type Err = SessionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut session = Session::default();
+        for (i, line) in s.lines().enumerate() {
+            let error = |message: &str| SessionError { line: i + 1, message: format!("{} in {}", message, line) };
+            let words = line.split_whitespace().collect::<Vec<_>>();
+            match words[..] {
+                [] => (),
+                ["set", address, value] => match (address.parse(), value.parse()) {
+                    (Ok(address), Ok(value)) if session.entries.is_empty() => session.memory.push((address, value)),
+                    (Ok(_), Ok(_)) => return Err(error("memory set after the first entry")),
+                    _ => return Err(error("wrong address or value"))
+                },
+                [cycle, direction, value] => {
+                    let (cycle, value) = match (cycle.parse::<u64>(), value.parse()) {
+                        (Ok(cycle), Ok(value)) => (cycle, value),
+                        _ => return Err(error("wrong cycle or value"))
+                    };
+                    let io = match direction {
+                        "in" => Io::Input(value),
+                        "out" => Io::Output(value),
+                        _ => return Err(error("expected in or out"))
+                    };
+                    if session.entries.last().is_some_and(|last| last.cycle >= cycle) {
+                        return Err(error("cycles must increase"));
+                    }
+                    session.entries.push(Entry { cycle, io });
+                },
+                _ => return Err(error("expected 3 words"))
+            }
+        }
+        Ok(session)
+    }
+}
+
+impl Session {
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Session> {
+        fs::read_to_string(path)?
+            .parse()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    pub fn inputs(&self) -> Vec<i64> {
+        self.entries.iter().filter_map(|entry| match entry.io {
+            Io::Input(value) => Some(value),
+            Io::Output(_) => None
+        }).collect()
+    }
+
+    pub fn outputs(&self) -> Vec<i64> {
+        self.entries.iter().filter_map(|entry| match entry.io {
+            Io::Input(_) => None,
+            Io::Output(value) => Some(value)
+        }).collect()
+    }
+
+    // Entries before the input at the index, the program waits for it once they are replayed
+    pub fn truncated(&self, inputs: usize) -> Session {
+        let end = self.entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| matches!(entry.io, Io::Input(_)))
+            .nth(inputs)
+            .map_or(self.entries.len(), |(i, _)| i);
+        Session { memory: self.memory.clone(), entries: self.entries[..end].to_vec() }
+    }
+
+    // Cycle of the last entry, 0 without any
+    pub fn cycles(&self) -> u64 {
+        self.entries.last().map_or(0, |entry| entry.cycle)
+    }
+
+    // Runs the program with the recorded inputs until it ends or waits for more, and
+    // compares its session with this one
+    pub fn replay(&self, codes: Vec<i64>) -> Result<(), ReplayError> {
+        let mut program = IntCode::new(codes);
+        for &(address, value) in &self.memory {
+            program.poke(address, value);
+        }
+        program.write(&self.inputs());
+        program.set_session(Session { memory: self.memory.clone(), entries: Vec::new() });
+        program.process().map_err(ReplayError::IntCode)?;
+        let replayed = program.take_session().unwrap_or_default();
+        let len = self.entries.len().max(replayed.entries.len());
+        match (0..len).find(|&i| self.entries.get(i) != replayed.entries.get(i)) {
+            Some(index) => Err(ReplayError::Mismatch {
+                index,
+                expected: self.entries.get(index).copied(),
+                found: replayed.entries.get(index).copied()
+            }),
+            None => Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+// Echoes its inputs doubled until it reads 0
+const DOUBLE: [i64; 16] = [3,15,1006,15,14,102,2,15,15,4,15,1105,1,0,99,0];
+
+#[test]
+fn test0_session() {
+    let mut program = IntCode::new(DOUBLE.to_vec());
+    program.set_session(Session::default());
+    program.write(&[3, 5]);
+    program.process().unwrap();
+    let start = program.snapshot();
+    program.write(&[4]);
+    program.process().unwrap();
+    program.restore(&start);
+    program.write(&[0]);
+    program.process().unwrap();
+
+    // the session forgets the input of 4
+    let session = program.take_session().unwrap();
+    assert_eq!(session.to_string(), "0 in 3\n3 out 6\n5 in 5\n8 out 10\n10 in 0\n");
+    assert_eq!((session.inputs(), session.outputs(), session.cycles()), (vec![3, 5, 0], vec![6, 10], 10));
+    assert_eq!("0 in 3\n3 out 6\n5 in 5\n8 out 10\n10 in 0\n".parse(), Ok(session.clone()));
+    assert_eq!(session.replay(DOUBLE.to_vec()), Ok(()));
+
+    // the program no longer doubles
+    let mut codes = DOUBLE.to_vec();
+    codes[6] = 1;
+    assert_eq!(session.replay(codes), Err(ReplayError::Mismatch {
+        index: 1,
+        expected: Some(Entry { cycle: 3, io: Io::Output(6) }),
+        found: Some(Entry { cycle: 3, io: Io::Output(3) })
+    }));
+}
+
+#[test]
+fn test1_session() {
+    assert_eq!("set 0 2\n5 out 1\n".parse::<Session>().map(|session| session.memory), Ok(vec![(0, 2)]));
+    let error = |s: &str| s.parse::<Session>().unwrap_err().to_string();
+    assert_eq!(error("1 in 2\n1 out 2"), "line 2: cycles must increase in 1 out 2");
+    assert_eq!(error("1 inout 2"), "line 1: expected in or out in 1 inout 2");
+    assert_eq!(error("1 in 2\nset 0 1"), "line 2: memory set after the first entry in set 0 1");
+    assert_eq!(error("1 in"), "line 1: expected 3 words in 1 in");
+}
+
+#[test]
+fn test2_session() {
+    // truncated sessions of the interactive days, recorded with `record DAY input/sessions/dayXX.txt INPUTS`
+    for day in [13, 15, 25] {
+        let session = Session::load(format!("./input/sessions/day{:02}.txt", day)).unwrap();
+        assert!(!session.inputs().is_empty() && !session.outputs().is_empty(), "day {}", day);
+        let contents = fs::read_to_string(format!("./input/day{:02}.txt", day)).unwrap();
+        let program = crate::intcode::extract_codes(&contents).unwrap();
+        assert_eq!(session.replay(program), Ok(()), "day {}", day);
+    }
+}
+
+#[test]
+fn test3_session() {
+    // going back forgets the values of the steps undone, running again records them once
+    let mut program = IntCode::new(DOUBLE.to_vec());
+    program.set_history(crate::history::History::new(100));
+    program.set_session(Session::default());
+    program.write(&[3, 0]);
+    program.process().unwrap();
+    (0..6).for_each(|_| assert!(program.step_back().is_some()));
+    program.process().unwrap();
+    let session = program.take_session().unwrap();
+    assert_eq!(session.to_string(), "0 in 3\n3 out 6\n5 in 0\n");
+    assert_eq!(session.to_string().parse(), Ok(session));
+}
+
+#[test]
+fn test4_session() {
+    // a session cut before an input replays as a prefix of the run
+    let mut program = IntCode::new(DOUBLE.to_vec());
+    program.set_session(Session::default());
+    program.write(&[3, 0]);
+    program.process().unwrap();
+    let session = program.take_session().unwrap().truncated(1);
+    assert_eq!(session.to_string(), "0 in 3\n3 out 6\n");
+    assert_eq!(session.replay(DOUBLE.to_vec()), Ok(()));
+}
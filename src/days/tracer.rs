@@ -0,0 +1,239 @@
+//
+// Execution tracer and profiler for IntCode programs
+//
+// The trace is a compact binary stream: an 8 bytes header followed by one record per
+// executed instruction. Numbers are LEB128 varints, signed ones zigzag encoded
+//   position, instruction, parameters, [write address, written value], [next position if jump]
+// The write is present for instructions writing memory, the next position for jumps
+//
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use crate::intcode::{ Instruction, Opcode };
+
+const HEADER: &[u8; 8] = b"ICTRACE1";
+const TOP: usize = 10;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub pos: usize,
+    pub instruction: i64,
+    pub params: Vec<i64>,
+    pub write: Option<(usize, i64)>,
+    pub next: usize
+}
+
+#[derive(Debug, Default)]
+pub struct Profile {
+    pub cycles: u64,
+    pub opcodes: HashMap<Opcode, u64>,
+    pub addresses: HashMap<usize, u64>,
+    // backward jumps taken, from the jump address to its target
+    pub loops: HashMap<(usize, usize), u64>
+}
+
+pub struct Tracer {
+    trace: Option<Vec<u8>>,
+    profile: Profile
+}
+
+impl Tracer {
+    // Records every instruction and profiles them
+    pub fn new() -> Self {
+        Tracer { trace: Some(HEADER.to_vec()), profile: Profile::default() }
+    }
+
+    // Only aggregates counters, without keeping the trace
+    pub fn profiler() -> Self {
+        Tracer { trace: None, profile: Profile::default() }
+    }
+
+    pub fn record(&mut self, record: &Record) {
+        let opcode = Opcode::from_code(record.instruction % 100);
+
+        self.profile.cycles += 1;
+        if let Some(opcode) = opcode {
+            *self.profile.opcodes.entry(opcode).or_insert(0) += 1;
+        }
+        *self.profile.addresses.entry(record.pos).or_insert(0) += 1;
+        if matches!(opcode, Some(Opcode::Jnz) | Some(Opcode::Jz)) && record.next <= record.pos {
+            *self.profile.loops.entry((record.pos, record.next)).or_insert(0) += 1;
+        }
+
+        if let Some(trace) = &mut self.trace {
+            write_varint(trace, record.pos as u64);
+            write_varint(trace, zigzag(record.instruction));
+            for &param in &record.params {
+                write_varint(trace, zigzag(param));
+            }
+            if let Some((address, value)) = record.write {
+                write_varint(trace, address as u64);
+                write_varint(trace, zigzag(value));
+            }
+            if matches!(opcode, Some(Opcode::Jnz) | Some(Opcode::Jz)) {
+                write_varint(trace, record.next as u64);
+            }
+        }
+    }
+
+    pub fn profile(&self) -> &Profile {
+        &self.profile
+    }
+
+    pub fn trace(&self) -> Option<&[u8]> {
+        self.trace.as_deref()
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.trace.as_deref().unwrap_or(HEADER))
+    }
+}
+
+impl Default for Tracer {
+    fn default() -> Self {
+        Tracer::new()
+    }
+}
+
+pub fn decode(trace: &[u8]) -> io::Result<Vec<Record>> {
+    let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+    if !trace.starts_with(HEADER) {
+        return Err(invalid("not an IntCode trace"));
+    }
+    let mut bytes = trace[HEADER.len()..].iter().copied();
+    let mut records = Vec::new();
+    while let Some(pos) = read_varint(&mut bytes, true)? {
+        let mut next = || read_varint(&mut bytes, false).map(|v| v.unwrap());
+        let pos = pos as usize;
+        let instruction = unzigzag(next()?);
+        let decoded = Instruction::decode(instruction).ok_or_else(|| invalid("wrong instruction in trace"))?;
+        let params = (0..decoded.opcode.params()).map(|_| next().map(unzigzag)).collect::<io::Result<Vec<_>>>()?;
+        let write = match decoded.opcode.output_param() {
+            Some(_) => Some((next()? as usize, unzigzag(next()?))),
+            None => None
+        };
+        let next = match decoded.opcode {
+            Opcode::Jnz | Opcode::Jz => next()? as usize,
+            Opcode::Hlt => pos,
+            _ => pos + decoded.size()
+        };
+        records.push(Record { pos, instruction, params, write, next });
+    }
+    Ok(records)
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let percent = |count: u64| 100.0 * count as f64 / self.cycles.max(1) as f64;
+        fn sorted<K: Copy + Ord>(counts: &HashMap<K, u64>) -> Vec<(K, u64)> {
+            let mut counts = counts.iter().map(|(k, c)| (*k, *c)).collect::<Vec<_>>();
+            counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+            counts
+        }
+
+        writeln!(f, "cycles: {}", self.cycles)?;
+        writeln!(f, "opcodes:")?;
+        let mut opcodes = self.opcodes.iter().map(|(op, c)| (*op, *c)).collect::<Vec<_>>();
+        opcodes.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.code().cmp(&b.0.code())));
+        for (opcode, count) in opcodes {
+            writeln!(f, "  {:<4} {:>12} {:>6.2}%", opcode.mnemonic(), count, percent(count))?;
+        }
+        writeln!(f, "hot addresses:")?;
+        for (address, count) in sorted(&self.addresses).into_iter().take(TOP) {
+            writeln!(f, "  {:>5} {:>12} {:>6.2}%", address, count, percent(count))?;
+        }
+        writeln!(f, "hot loops:")?;
+        for ((from, to), count) in sorted(&self.loops).into_iter().take(TOP) {
+            writeln!(f, "  {:>5}..{:<5} {:>12} iterations", to, from, count)?;
+        }
+        Ok(())
+    }
+}
+
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+fn unzigzag(value: u64) -> i64 {
+    (value >> 1) as i64 ^ -((value & 1) as i64)
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+// None at the end of the stream, which is only allowed before the start of a record
+fn read_varint(bytes: &mut impl Iterator<Item = u8>, at_record_start: bool) -> io::Result<Option<u64>> {
+    let mut value = 0;
+    let mut shift = 0;
+    loop {
+        match bytes.next() {
+            Some(byte) if shift < 64 => {
+                value |= ((byte & 0x7f) as u64) << shift;
+                if byte & 0x80 == 0 {
+                    return Ok(Some(value));
+                }
+                shift += 7;
+            },
+            None if shift == 0 && at_record_start => return Ok(None),
+            _ => return Err(io::Error::new(io::ErrorKind::InvalidData, "truncated trace"))
+        }
+    }
+}
+
+#[test]
+fn test0_trace() {
+    use crate::intcode::IntCode;
+
+    let mut program = IntCode::new(vec![3,12,4,12,1001,12,-1,12,1005,12,2,99,0]);
+    program.set_tracer(Tracer::new());
+    program.write_one(2);
+    program.process().unwrap();
+    let tracer = program.take_tracer().unwrap();
+    let records = decode(tracer.trace().unwrap()).unwrap();
+    assert_eq!(records.len(), 8);
+    assert_eq!(records[0], Record { pos: 0, instruction: 3, params: vec![12], write: Some((12, 2)), next: 2 });
+    assert_eq!(records[3], Record { pos: 8, instruction: 1005, params: vec![12, 2], write: None, next: 2 });
+    assert_eq!(records[6], Record { pos: 8, instruction: 1005, params: vec![12, 2], write: None, next: 11 });
+    assert_eq!(records[7], Record { pos: 11, instruction: 99, params: vec![], write: None, next: 11 });
+    assert_eq!(decode(&tracer.trace().unwrap()[..20]).err().map(|e| e.kind()), Some(io::ErrorKind::InvalidData));
+}
+
+#[test]
+fn test1_profile() {
+    use crate::intcode::IntCode;
+
+    let mut program = IntCode::new(vec![3,12,4,12,1001,12,-1,12,1005,12,2,99,0]);
+    program.set_tracer(Tracer::profiler());
+    program.write_one(3);
+    program.process().unwrap();
+    let tracer = program.take_tracer().unwrap();
+    assert!(tracer.trace().is_none());
+    let profile = tracer.profile();
+    assert_eq!(profile.cycles, 11);
+    assert_eq!(profile.opcodes[&Opcode::Out], 3);
+    assert_eq!(profile.addresses[&8], 3);
+    assert_eq!(profile.loops[&(8, 2)], 2);
+    assert_eq!(profile.to_string(), "\
+        cycles: 11\n\
+        opcodes:\n  \
+          ADD             3  27.27%\n  \
+          OUT             3  27.27%\n  \
+          JNZ             3  27.27%\n  \
+          IN              1   9.09%\n  \
+          HLT             1   9.09%\n\
+        hot addresses:\n      \
+              2            3  27.27%\n      \
+              4            3  27.27%\n      \
+              8            3  27.27%\n      \
+              0            1   9.09%\n     \
+             11            1   9.09%\n\
+        hot loops:\n      \
+              2..8                2 iterations\n");
+}
@@ -0,0 +1,82 @@
+//
+// Where the puzzle inputs come from, so that the same binary runs anybody's inputs
+//
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::{ self, Read };
+use std::path::PathBuf;
+use crate::Day;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum Source {
+    // ./input/dayXX.txt
+    #[default]
+    Default,
+    // a file, or a directory holding dayXX.txt files
+    Path(PathBuf),
+    Stdin,
+    Inline(String)
+}
+
+#[derive(Debug)]
+pub struct InputError {
+    pub day: usize,
+    pub origin: String,
+    pub error: io::Error
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "day {}: cannot read {}: {}", self.day, self.origin, self.error)
+    }
+}
+
+impl Error for InputError {}
+
+impl Source {
+    // Single inputs can't be shared between days, unlike files found by day number
+    pub fn is_single(&self) -> bool {
+        match self {
+            Source::Path(path) => !path.is_dir(),
+            Source::Stdin | Source::Inline(_) => true,
+            Source::Default => false
+        }
+    }
+
+    pub fn read(&self, day: &Day) -> Result<String, InputError> {
+        let error = |origin: String, error| InputError { day: day.day, origin, error };
+        let path = match self {
+            Source::Default => PathBuf::from(day.input_path()),
+            Source::Path(path) if path.is_dir() => path.join(format!("day{:02}.txt", day.day)),
+            Source::Path(path) => path.clone(),
+            Source::Stdin => {
+                let mut contents = String::new();
+                return io::stdin()
+                    .read_to_string(&mut contents)
+                    .map(|_| contents)
+                    .map_err(|e| error("standard input".to_string(), e));
+            },
+            Source::Inline(text) => return Ok(text.clone())
+        };
+        fs::read_to_string(&path).map_err(|e| error(path.display().to_string(), e))
+    }
+}
+
+#[test]
+fn test0_source() {
+    let day = crate::day(4).unwrap();
+    assert_eq!(Source::Inline("111111-111122".to_string()).read(day).unwrap(), "111111-111122");
+    assert!(Source::Inline(String::new()).is_single());
+
+    let directory = std::env::temp_dir().join("input_test0_source");
+    fs::create_dir_all(&directory).unwrap();
+    fs::write(directory.join("day04.txt"), "123-456").unwrap();
+    let source = Source::Path(directory.clone());
+    assert!(!source.is_single());
+    assert_eq!(source.read(day).unwrap(), "123-456");
+    let error = source.read(crate::day(5).unwrap()).unwrap_err();
+    assert_eq!(error.error.kind(), io::ErrorKind::NotFound);
+    assert!(error.to_string().starts_with(&format!("day 5: cannot read {}", directory.join("day05.txt").display())));
+    fs::remove_dir_all(&directory).unwrap();
+}
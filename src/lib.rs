@@ -0,0 +1,430 @@
+//
+// Solutions of Advent of Code 2019, for the binary as well as for other crates
+//
+// Each day reads its puzzle input as text and returns the answers of its two stars
+//
+pub mod days;
+pub mod bench;
+pub mod input;
+pub mod memory;
+pub mod pool;
+pub mod report;
+pub mod scaffold;
+pub mod verify;
+pub mod watch;
+use days::*;
+use std::convert::TryFrom;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Answer {
+    Integer(i64),
+    Text(String),
+    // lines of pixels, drawn with characters
+    Image(String)
+}
+
+impl Answer {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Answer::Integer(_) => "integer",
+            Answer::Text(_) => "string",
+            Answer::Image(_) => "image"
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Integer(value) => write!(f, "{}", value),
+            Answer::Text(text) => write!(f, "{}", text),
+            Answer::Image(image) => write!(f, "{}", image)
+        }
+    }
+}
+
+// Integers too large for an i64 are kept whole as text
+macro_rules! integer_answer {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Answer {
+            fn from(value: $t) -> Self {
+                i64::try_from(value).map_or_else(|_| Answer::Text(value.to_string()), Answer::Integer)
+            }
+        })*
+    };
+}
+
+integer_answer!(i32, i64, i128, u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        Answer::Text(text)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Self {
+        Answer::Text(text.to_string())
+    }
+}
+
+pub trait Solution {
+    // what the answers of the two stars are
+    fn titles(&self) -> [&'static str; 2];
+    fn first_star(&self, input: &str) -> Answer;
+    fn second_star(&self, input: &str) -> Answer;
+
+    // Stars are numbered from 1, None for any other star
+    fn star(&self, star: usize, input: &str) -> Option<Answer> {
+        match star {
+            1 => Some(self.first_star(input)),
+            2 => Some(self.second_star(input)),
+            _ => None
+        }
+    }
+}
+
+// Event whose solutions are in the days module, the other years have a module of their own
+pub const YEAR: usize = 2019;
+
+// Day of the registry, with the star functions of its module
+pub struct Day {
+    pub year: usize,
+    pub day: usize,
+    pub titles: [&'static str; 2],
+    pub stars: [fn(&str) -> Answer; 2],
+    // parsing of the input alone, which the stars start with, so bench times it apart.
+    // None when parsing goes along with solving
+    pub parse: Option<fn(&str)>
+}
+
+impl Day {
+    // Puzzle input, relative to the root of the repository, in a directory per year but YEAR
+    pub fn input_path(&self) -> String {
+        if self.year == YEAR {
+            format!("./input/day{:02}.txt", self.day)
+        } else {
+            format!("./input/{}/day{:02}.txt", self.year, self.day)
+        }
+    }
+}
+
+// Days are identified by their year and number
+impl fmt::Debug for Day {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Day({}, {})", self.year, self.day)
+    }
+}
+
+impl PartialEq for Day {
+    fn eq(&self, other: &Self) -> bool {
+        (self.year, self.day) == (other.year, other.day)
+    }
+}
+
+impl Solution for Day {
+    fn titles(&self) -> [&'static str; 2] {
+        self.titles
+    }
+
+    fn first_star(&self, input: &str) -> Answer {
+        (self.stars[0])(input)
+    }
+
+    fn second_star(&self, input: &str) -> Answer {
+        (self.stars[1])(input)
+    }
+}
+
+pub static DAYS: [Day; 25] = [
+    Day {
+        year: YEAR,
+        day: 1,
+        titles: [
+            "sum of the fuel requirements",
+            "sum of the fuel requirements"
+        ],
+        stars: [day01::first_star, day01::second_star],
+        parse: None
+    },
+    Day {
+        year: YEAR,
+        day: 2,
+        titles: [
+            "value at position 0",
+            "100 * noun + verb"
+        ],
+        stars: [day02::first_star, day02::second_star],
+        parse: Some(intcode::parse)
+    },
+    Day {
+        year: YEAR,
+        day: 3,
+        titles: [
+            "manhattan distance to closest intersection",
+            "fewest combined steps to reach an intersection"
+        ],
+        stars: [day03::first_star, day03::second_star],
+        parse: None
+    },
+    Day {
+        year: YEAR,
+        day: 4,
+        titles: [
+            "count of different passwords",
+            "count of different passwords"
+        ],
+        stars: [day04::first_star, day04::second_star],
+        parse: None
+    },
+    Day {
+        year: YEAR,
+        day: 5,
+        titles: [
+            "diagnostic code for input 1",
+            "diagnostic code for system ID 5"
+        ],
+        stars: [day05::first_star, day05::second_star],
+        parse: Some(intcode::parse)
+    },
+    Day {
+        year: YEAR,
+        day: 6,
+        titles: [
+            "total number of direct and indirect orbits",
+            "minimum number of orbital transfers"
+        ],
+        stars: [day06::first_star, day06::second_star],
+        parse: Some(day06::parse)
+    },
+    Day {
+        year: YEAR,
+        day: 7,
+        titles: [
+            "highest signal that can be sent to the thrusters",
+            "highest signal that can be sent to the thrusters"
+        ],
+        stars: [day07::first_star, day07::second_star],
+        parse: Some(intcode::parse)
+    },
+    Day {
+        year: YEAR,
+        day: 8,
+        titles: [
+            "number of 1 digits multiplied by the number of 2 digits",
+            "message produced after decoding the image"
+        ],
+        stars: [day08::first_star, day08::second_star],
+        parse: None
+    },
+    Day {
+        year: YEAR,
+        day: 9,
+        titles: [
+            "BOOST keycode",
+            "coordinates of the distress signal"
+        ],
+        stars: [day09::first_star, day09::second_star],
+        parse: Some(intcode::parse)
+    },
+    Day {
+        year: YEAR,
+        day: 10,
+        titles: [
+            "number of asteroids detected",
+            "coordinates of 200th vaporized asteroid (100 * x + y)"
+        ],
+        stars: [day10::first_star, day10::second_star],
+        parse: Some(day10::parse)
+    },
+    Day {
+        year: YEAR,
+        day: 11,
+        titles: [
+            "number of panels painted at least once",
+            "painted registration identifier"
+        ],
+        stars: [day11::first_star, day11::second_star],
+        parse: Some(intcode::parse)
+    },
+    Day {
+        year: YEAR,
+        day: 12,
+        titles: [
+            "total energy after 1000 steps",
+            "steps to reach first state"
+        ],
+        stars: [day12::first_star, day12::second_star],
+        parse: Some(day12::parse)
+    },
+    Day {
+        year: YEAR,
+        day: 13,
+        titles: [
+            "num of block tiles on the screen when the game exits",
+            "final score after the last block is broken"
+        ],
+        stars: [day13::first_star, day13::second_star],
+        parse: Some(intcode::parse)
+    },
+    Day {
+        year: YEAR,
+        day: 14,
+        titles: [
+            "minimum amount of ORE required to produce exactly 1 FUEL",
+            "amount of FUEL produced with 1 trillion of ORE"
+        ],
+        stars: [day14::first_star, day14::second_star],
+        parse: Some(day14::parse)
+    },
+    Day {
+        year: YEAR,
+        day: 15,
+        titles: [
+            "fewest number of movement commands",
+            "minutes to fill with oxygen"
+        ],
+        stars: [day15::first_star, day15::second_star],
+        parse: Some(intcode::parse)
+    },
+    Day {
+        year: YEAR,
+        day: 16,
+        titles: [
+            "first eight digits in the final output list",
+            "eight-digit message embedded in the final output list"
+        ],
+        stars: [day16::first_star, day16::second_star],
+        parse: Some(day16::parse)
+    },
+    Day {
+        year: YEAR,
+        day: 17,
+        titles: [
+            "sum of alignment parameters",
+            "dust collected by vacuum robot"
+        ],
+        stars: [day17::first_star, day17::second_star],
+        parse: Some(intcode::parse)
+    },
+    Day {
+        year: YEAR,
+        day: 18,
+        titles: [
+            "steps of shortest path that collects all of the keys",
+            "fewest steps necessary to collect all of the keys"
+        ],
+        stars: [day18::first_star, day18::second_star],
+        parse: Some(day18::parse)
+    },
+    Day {
+        year: YEAR,
+        day: 19,
+        titles: [
+            "points affected by the tractor beam in the 50x50 area closest to the emitter",
+            "coordinates of point closest to the emitter of the 100x100 square (10000 * x + y)"
+        ],
+        stars: [day19::first_star, day19::second_star],
+        parse: Some(intcode::parse)
+    },
+    Day {
+        year: YEAR,
+        day: 20,
+        titles: [
+            "steps to get from the open tile marked AA to the open tile marked ZZ",
+            "steps to get from the open tile marked AA to the open tile marked ZZ, both at the outermost layer"
+        ],
+        stars: [day20::first_star, day20::second_star],
+        parse: Some(day20::parse)
+    },
+    Day {
+        year: YEAR,
+        day: 21,
+        titles: [
+            "amount of reported damage to the hull",
+            "amount of reported damage to the hull"
+        ],
+        stars: [day21::first_star, day21::second_star],
+        parse: Some(intcode::parse)
+    },
+    Day {
+        year: YEAR,
+        day: 22,
+        titles: [
+            "position of card 2019",
+            "card at position 2020"
+        ],
+        stars: [day22::first_star, day22::second_star],
+        parse: None
+    },
+    Day {
+        year: YEAR,
+        day: 23,
+        titles: [
+            "Y value of the first packet sent to address 255",
+            "Y value delivered by the NAT to the computer at address 0 twice in a row"
+        ],
+        stars: [day23::first_star, day23::second_star],
+        parse: Some(intcode::parse)
+    },
+    Day {
+        year: YEAR,
+        day: 24,
+        titles: [
+            "biodiversity rating for the first layout that appears twice",
+            "bugs present after 200 minutes"
+        ],
+        stars: [day24::first_star, day24::second_star],
+        parse: None
+    },
+    Day {
+        year: YEAR,
+        day: 25,
+        titles: [
+            "password for the main airlock",
+            "THE END"
+        ],
+        stars: [day25::first_star, day25::second_star],
+        parse: Some(intcode::parse)
+    }
+];
+
+pub struct Year {
+    pub year: usize,
+    pub days: &'static [Day]
+}
+
+impl Year {
+    pub fn day(&self, day: usize) -> Option<&'static Day> {
+        self.days.iter().find(|d| d.day == day)
+    }
+}
+
+// Years of the registry, the `new` command adds the ones it creates
+pub static YEARS: &[Year] = &[
+    Year { year: YEAR, days: &DAYS }
+];
+
+pub fn year(year: usize) -> Option<&'static Year> {
+    YEARS.iter().find(|y| y.year == year)
+}
+
+// Day of YEAR, numbered from 1
+pub fn day(day: usize) -> Option<&'static Day> {
+    DAYS.get(day.checked_sub(1)?)
+}
+
+#[test]
+fn test0_days() {
+    assert!(DAYS.iter().enumerate().all(|(i, day)| day.year == YEAR && day.day == i + 1));
+    assert_eq!(year(YEAR).and_then(|year| year.day(25)).map(|day| day.titles[1]), Some("THE END"));
+    assert!(day(0).is_none() && day(26).is_none());
+    let day = day(1).unwrap();
+    assert_eq!(day.star(1, "12\n14\n1969\n100756\n"), Some(Answer::Integer(2 + 2 + 654 + 33583)));
+    assert_eq!(day.star(3, ""), None);
+    assert_eq!(Answer::from(42usize).to_string(), "42");
+    assert_eq!(Answer::from(u64::MAX), Answer::Text("18446744073709551615".to_string()));
+    assert_eq!(Answer::from(i128::MIN + 1).to_string(), "-170141183460469231731687303715884105727");
+    assert_eq!(Answer::from("ABC"), Answer::Text("ABC".to_string()));
+}
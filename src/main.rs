@@ -1,62 +1,316 @@
-mod days;
-use days::*;
+mod cli;
+
+use adventofcode2019::Day;
+use adventofcode2019::bench;
+use adventofcode2019::input::Source;
+use adventofcode2019::memory;
+use adventofcode2019::pool;
+use adventofcode2019::report;
+use adventofcode2019::scaffold;
+use adventofcode2019::verify::{ self, Outcome };
+use adventofcode2019::watch;
+use adventofcode2019::days::*;
+use cli::Command;
+use std::collections::HashMap;
 use std::env;
+use std::fs;
+use std::io;
+use std::panic;
+use std::path::{ Path, PathBuf };
+use std::process;
+use std::thread;
+
+// Counts nothing until the heap usage of the stars is asked for
+#[global_allocator]
+static ALLOCATOR: memory::Counting = memory::Counting;
 
 fn main() {
-    let days = [
-        [day01::first_star, day01::second_star],
-        [day02::first_star, day02::second_star],
-        [day03::first_star, day03::second_star],
-        [day04::first_star, day04::second_star],
-        [day05::first_star, day05::second_star],
-        [day06::first_star, day06::second_star],
-        [day07::first_star, day07::second_star],
-        [day08::first_star, day08::second_star],
-        [day09::first_star, day09::second_star],
-        [day10::first_star, day10::second_star],
-        [day11::first_star, day11::second_star],
-        [day12::first_star, day12::second_star],
-        [day13::first_star, day13::second_star],
-        [day14::first_star, day14::second_star],
-        [day15::first_star, day15::second_star],
-        [day16::first_star, day16::second_star],
-        [day17::first_star, day17::second_star],
-        [day18::first_star, day18::second_star],
-        [day19::first_star, day19::second_star],
-        [day20::first_star, day20::second_star],
-        [day21::first_star, day21::second_star],
-        [day22::first_star, day22::second_star],
-        [day23::first_star, day23::second_star],
-        [day24::first_star, day24::second_star],
-        [day25::first_star, day25::second_star]
-    ];
-
-    let args: Vec<String> = env::args().collect();
-    if args.len() > 1 {
-        let day = args[1]
-            .parse::<usize>()
-            .unwrap_or_else(|_|
-                panic!("you must enter a number between 1 and {}", days.len())) - 1;
-        if day >= days.len() {
-            panic!("you must enter a number between 1 and {}", days.len());
-        } else if args.len() > 2 {
-            let star = args[2].parse::<usize>().expect("you must enter a number between 1 and 2") - 1;
-            if star > 1 {
-                panic!("you must enter a number between 1 and 2");
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let command = cli::parse(&args).unwrap_or_else(|e| {
+        eprintln!("error: {}", e);
+        process::exit(2);
+    });
+    if let Err(message) = execute(command) {
+        eprintln!("error: {}", message);
+        process::exit(1);
+    }
+}
+
+fn execute(command: Command) -> Result<(), String> {
+    match command {
+        Command::Run { days, stars, source, json, workers, memory } => {
+            if memory {
+                memory::enable();
+            }
+            let failed = run_stars(&days, &stars, &source, json, workers, memory);
+            if failed > 0 {
+                return Err(format!("{} stars without answer", failed));
+            }
+        },
+        Command::Verify { answers, days, source } => {
+            let expected = verify::load(&answers)?;
+            if !verify_days(&expected, &days, &source) {
+                return Err("some answers are not the expected ones".to_string());
+            }
+        },
+        Command::Bench { days, stars, source, runs, csv, save, baseline, tolerance } => {
+            let baseline = match baseline {
+                Some(path) => Some(bench::parse_baseline(&read_file(&path)?).map_err(|e| format!("{}: {}", path, e))?),
+                None => None
+            };
+            let mut timings = Vec::new();
+            let mut failed = 0;
+            for day in days {
+                for &star in &stars {
+                    match bench::measure(day, star, &source, runs) {
+                        Ok(timing) => timings.push(timing),
+                        Err(e) => {
+                            eprintln!("day {:>2}.{} FAIL cannot read {}: {}", day.day, star, e.origin, e.error);
+                            failed += 1;
+                        }
+                    }
+                }
+            }
+            if csv {
+                print!("{}", bench::csv(&timings));
             } else {
-                days[day][star]();
+                print!("{}", bench::table(&timings, baseline.as_ref(), tolerance));
             }
-        } else {
-            for star in days[day].iter() {
-                star();
+            if let Some(path) = save {
+                write_file(&path, &bench::csv(&timings))?;
+            }
+            let slower = baseline.map_or(0, |baseline| timings.iter().filter(|t| t.slowdown(&baseline, tolerance).is_some()).count());
+            if failed > 0 {
+                return Err(format!("{} stars not timed", failed));
+            }
+            if slower > 0 {
+                return Err(format!("{} stars slower than the baseline", slower));
             }
+        },
+        Command::Watch { days } => watch_days(&days),
+        Command::List { year } => {
+            for day in adventofcode2019::year(year).map_or(&[][..], |year| year.days) {
+                for (star, title) in day.titles.iter().enumerate() {
+                    println!("day {:>2}.{} - {}", day.day, star + 1, title);
+                }
+            }
+        },
+        Command::New { year, day } => {
+            for path in scaffold::generate(Path::new("."), year, day)? {
+                println!("{}", path.display());
+            }
+        },
+        Command::Help(help) => print!("{}", help),
+        Command::Disasm { program } => print!("{}", disassembler::listing(&read_program(&program)?)),
+        Command::Asm { source, output } => {
+            let codes = assembler::assemble(&read_file(&source)?).map_err(|e| format!("{}: {}", source, e))?;
+            let program = codes.iter().map(|c| c.to_string()).collect::<Vec<_>>().join(",");
+            match output {
+                Some(path) => write_file(&path, &program)?,
+                None => println!("{}", program)
+            }
+        },
+        Command::Compile { program, output } => {
+            let source = compiler::translate(&read_program(&program)?);
+            match output {
+                Some(path) => write_file(&path, &source)?,
+                None => print!("{}", source)
+            }
+        },
+        Command::Cfg { program, graph } => {
+            let codes = read_program(&program)?;
+            let analysis = analysis::analyze(&codes);
+            print!("{}", analysis.summary(&codes));
+            if let Some(path) = graph {
+                write_file(&path, &analysis.dot(&codes))?;
+            }
+        },
+        Command::Decompile { program, output } => {
+            let source = decompiler::pseudo_code(&read_program(&program)?);
+            match output {
+                Some(path) => write_file(&path, &source)?,
+                None => print!("{}", source)
+            }
+        },
+        Command::Debug { program } => {
+            let mut debugger = debugger::Debugger::new(intcode::IntCode::new(read_program(&program)?));
+            debugger::repl(&mut debugger, io::stdin().lock(), io::stdout()).map_err(|e| format!("terminal: {}", e))?;
+        },
+        Command::Exec { program, inputs, outputs } => {
+            let input = devices::FileInput::open(&inputs).map_err(|e| format!("cannot read {}: {}", inputs, e))?;
+            let output = devices::FileOutput::create(&outputs).map_err(|e| format!("cannot write {}: {}", outputs, e))?;
+            let mut machine = intcode::IntCode::with_device(read_program(&program)?, (input, output));
+            let status = machine.process();
+            machine.into_device().1.finish().map_err(|e| format!("cannot write {}: {}", outputs, e))?;
+            match status {
+                Ok(intcode::Status::End) => (),
+                Ok(_) => return Err(format!("{}: waiting for more inputs than {} has", program, inputs)),
+                Err(e) => return Err(format!("{}: {}", program, e))
+            }
+        },
+        Command::Trace { program, trace, inputs } => {
+            let tracer = run_traced(&program, tracer::Tracer::new(), inputs)?;
+            tracer.save(&trace).map_err(|e| format!("cannot write {}: {}", trace, e))?;
+            print!("{}", tracer.profile());
+        },
+        Command::Record { day, session, inputs } => {
+            let record = session_of(day).ok_or_else(|| format!("day {} has no interactive IntCode program, sessions are recorded for days 13, 15 and 25", day))?;
+            let path = session.unwrap_or_else(|| format!("input/sessions/day{:02}.txt", day));
+            if let Some(parent) = Path::new(&path).parent() {
+                fs::create_dir_all(parent).map_err(|e| format!("cannot create {}: {}", parent.display(), e))?;
+            }
+            let session = match (record(&read_file(&format!("input/day{:02}.txt", day))?), inputs) {
+                (session, Some(inputs)) => session.truncated(inputs),
+                (session, None) => session
+            };
+            write_file(&path, &session.to_string())?;
+            println!("{}: {} inputs and {} outputs in {} cycles", path, session.inputs().len(), session.outputs().len(), session.cycles());
+        },
+        Command::Replay { program, session: path } => {
+            let session = session::Session::load(&path).map_err(|e| format!("cannot read {}: {}", path, e))?;
+            session.replay(read_program(&program)?).map_err(|e| format!("{}: {}", path, e))?;
+            println!("{}: {} inputs and {} outputs in {} cycles, replayed", path, session.inputs().len(), session.outputs().len(), session.cycles());
+        },
+        Command::Profile { program, inputs } => {
+            let tracer = run_traced(&program, tracer::Tracer::profiler(), inputs)?;
+            print!("{}", tracer.profile());
         }
-    } else {
-        for day in days.iter() {
-            for star in day.iter() {
-                star();
+    }
+    Ok(())
+}
+
+// Runs every star and reports its outcome, returns whether all the expected answers were found
+fn verify_days(expected: &verify::Expected, days: &[&Day], source: &Source) -> bool {
+    panic::set_hook(Box::new(|_| ()));
+    let (mut passed, mut mismatched, mut failed, mut unchecked) = (0, 0, 0, 0);
+    for day in days {
+        let contents = source.read(day);
+        for star in 1..=2 {
+            let label = format!("day {:>2}.{}", day.day, star);
+            let outcome = match (&contents, expected.get(&(day.day, star))) {
+                (Err(e), _) => Outcome::Fail(format!("cannot read {}: {}", e.origin, e.error)),
+                (Ok(contents), Some(answer)) => verify::check(day, star, contents, answer),
+                (Ok(_), None) => {
+                    println!("{} UNCHECKED no expected answer", label);
+                    unchecked += 1;
+                    continue;
+                }
+            };
+            match outcome {
+                Outcome::Pass => {
+                    println!("{} PASS", label);
+                    passed += 1;
+                },
+                Outcome::Mismatch { expected, actual } => {
+                    println!("{} MISMATCH\n{}", label, verify::diff(&expected, &actual).trim_end());
+                    mismatched += 1;
+                },
+                Outcome::Fail(message) => {
+                    println!("{} FAIL {}", label, message);
+                    failed += 1;
+                }
             }
         }
     }
+    let _ = panic::take_hook();
+    println!("{} passed, {} mismatched, {} failed, {} unchecked", passed, mismatched, failed, unchecked);
+    mismatched + failed == 0
 }
 
+// Prints the report of each star in order, returns the number of stars without answer.
+// Inputs are read first, then stars are solved by the workers, with their heap usage
+// when memory is measured
+fn run_stars(days: &[&Day], stars: &[usize], source: &Source, json: bool, workers: usize, memory: bool) -> usize {
+    let jobs = days
+        .iter()
+        .flat_map(|&day| {
+            let contents = source.read(day).map_err(|e| format!("cannot read {}: {}", e.origin, e.error));
+            stars.iter().map(move |&star| (day, star, contents.clone()))
+        })
+        .collect::<Vec<_>>();
+    panic::set_hook(Box::new(|_| ()));
+    let mut failed = 0;
+    pool::ordered(&jobs, workers, |(day, star, contents)| match contents {
+        Ok(contents) if memory => report::measured(day, *star, contents),
+        Ok(contents) => report::run(day, *star, contents),
+        Err(e) => report::failed(day, *star, e.clone())
+    }, |(day, star, _), report| {
+        let report = report.unwrap_or_else(|message| report::failed(day, *star, message));
+        if json {
+            println!("{}", report.to_json());
+        } else {
+            println!("{}", report);
+        }
+        failed += report.answer.is_err() as usize;
+    });
+    let _ = panic::take_hook();
+    failed
+}
+
+// Solves the days, then the ones whose module or input change, until interrupted
+fn watch_days(days: &[&Day]) -> ! {
+    let files = |day: &Day| [watch::source_path(day), PathBuf::from(day.input_path())];
+    let mut watcher = watch::Watcher::new(days.iter().flat_map(|day| files(day)));
+    let mut previous = HashMap::new();
+    let mut changed = days.to_vec();
+    loop {
+        for day in changed {
+            println!("== day {} of {}", day.day, day.year);
+            match watch::solve(day) {
+                Ok(stars) => for star in stars {
+                    println!("{}", watch::compare(previous.get(&(star.day, star.star)), &star));
+                    previous.insert((star.day, star.star), star);
+                },
+                Err(message) => {
+                    println!("error: {}", message);
+                    continue;
+                }
+            }
+            match watch::test(day) {
+                Ok(summary) => println!("{}", summary),
+                Err(output) => println!("tests failed\n{}", output)
+            }
+        }
+        println!("watching {} files, ctrl-c to stop", days.len() * 2);
+        changed = Vec::new();
+        while changed.is_empty() {
+            thread::sleep(watch::POLL);
+            let paths = watcher.changed();
+            changed = days.iter().copied().filter(|&day| files(day).iter().any(|path| paths.contains(path))).collect();
+        }
+    }
+}
+
+// Sessions of the days driving an IntCode program
+fn session_of(day: usize) -> Option<fn(&str) -> session::Session> {
+    match day {
+        13 => Some(day13::session),
+        15 => Some(day15::session),
+        25 => Some(day25::session),
+        _ => None
+    }
+}
+
+fn read_file(path: &str) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|e| format!("cannot read {}: {}", path, e))
+}
+
+fn write_file(path: &str, contents: &str) -> Result<(), String> {
+    fs::write(path, contents).map_err(|e| format!("cannot write {}: {}", path, e))
+}
+
+fn read_program(path: &str) -> Result<Vec<i64>, String> {
+    intcode::extract_codes(&read_file(path)?).map_err(|e| format!("{}: {}", path, e))
+}
+
+// Runs the program with comma separated inputs until it ends or waits for more input
+fn run_traced(path: &str, tracer: tracer::Tracer, inputs: Option<String>) -> Result<tracer::Tracer, String> {
+    let mut program = intcode::IntCode::new(read_program(path)?);
+    if let Some(inputs) = inputs {
+        program.write(&intcode::extract_codes(&inputs).map_err(|e| format!("inputs: {}", e))?);
+    }
+    program.set_tracer(tracer);
+    let status = program.process();
+    eprintln!("{:?}, outputs {:?}", status, program.read());
+    Ok(program.take_tracer().unwrap())
+}
@@ -0,0 +1,129 @@
+//
+// Heap usage of the stars, counted by a global allocator
+//
+// The runner installs Counting as its global allocator, which forwards to the system one
+// and, once enabled, counts the allocations, the bytes they request and the bytes in use.
+// Counters are shared by all the threads, so stars must be measured one at a time. The
+// peak is the highest heap usage above the one when the measure starts
+//
+use std::alloc::{ GlobalAlloc, Layout, System };
+use std::fmt;
+use std::sync::atomic::{ AtomicBool, AtomicIsize, AtomicUsize, Ordering };
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static CURRENT: AtomicIsize = AtomicIsize::new(0);
+static PEAK: AtomicIsize = AtomicIsize::new(0);
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static BYTES: AtomicUsize = AtomicUsize::new(0);
+
+pub struct Counting;
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        allocated(layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        allocated(layout.size());
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        freed(layout.size());
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        freed(layout.size());
+        allocated(new_size);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+fn allocated(size: usize) {
+    if ENABLED.load(Ordering::Relaxed) {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        BYTES.fetch_add(size, Ordering::Relaxed);
+        let current = CURRENT.fetch_add(size as isize, Ordering::Relaxed) + size as isize;
+        PEAK.fetch_max(current, Ordering::Relaxed);
+    }
+}
+
+// Memory allocated before the measure can be freed during it, the current usage can
+// go below the one at the start, hence signed counters
+fn freed(size: usize) {
+    if ENABLED.load(Ordering::Relaxed) {
+        CURRENT.fetch_sub(size as isize, Ordering::Relaxed);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Usage {
+    // bytes in use at the peak, above the ones at the start
+    pub peak: usize,
+    pub allocations: usize,
+    // bytes requested by all the allocations
+    pub bytes: usize
+}
+
+impl fmt::Display for Usage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "peak {}, {} allocations of {}", size(self.peak), self.allocations, size(self.bytes))
+    }
+}
+
+// Turns counting on, it only measures anything when Counting is the global allocator
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+// Runs the closure and returns its usage of the heap, along with its result
+pub fn measure<R>(f: impl FnOnce() -> R) -> (R, Usage) {
+    let start = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(start, Ordering::Relaxed);
+    let (allocations, bytes) = (ALLOCATIONS.load(Ordering::Relaxed), BYTES.load(Ordering::Relaxed));
+    let result = f();
+    let usage = Usage {
+        peak: (PEAK.load(Ordering::Relaxed) - start).max(0) as usize,
+        allocations: ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+        bytes: BYTES.load(Ordering::Relaxed) - bytes
+    };
+    (result, usage)
+}
+
+// Bytes in binary units
+pub fn size(bytes: usize) -> String {
+    match bytes {
+        0..=1023 => format!("{}B", bytes),
+        1024..=1048575 => format!("{:.1}KiB", bytes as f64 / 1024.0),
+        _ => format!("{:.1}MiB", bytes as f64 / 1048576.0)
+    }
+}
+
+#[cfg(test)]
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+#[test]
+fn test0_memory() {
+    assert_eq!(size(512), "512B");
+    assert_eq!(size(1536), "1.5KiB");
+    assert_eq!(size(3 << 20), "3.0MiB");
+    assert_eq!(Usage { peak: 2048, allocations: 3, bytes: 4096 }.to_string(), "peak 2.0KiB, 3 allocations of 4.0KiB");
+
+    // other tests allocate on their own threads, only lower bounds hold
+    enable();
+    let (sum, usage) = measure(|| {
+        let first = vec![1u8; 1 << 20];
+        let second = vec![2u8; 1 << 20];
+        first.len() + second.len()
+    });
+    assert_eq!(sum, 2 << 20);
+    assert!(usage.peak >= 2 << 20);
+    assert!(usage.allocations >= 2 && usage.bytes >= 2 << 20);
+}
@@ -0,0 +1,82 @@
+//
+// Pool of worker threads running independent jobs
+//
+// Workers take the next job as soon as they are free, results are handed back in the
+// order of the jobs, as soon as the ones before them are done. A job that panics gives
+// its panic message instead of a result, and its worker goes on with the next jobs
+//
+use std::collections::BTreeMap;
+use std::panic::{ self, AssertUnwindSafe };
+use std::sync::atomic::{ AtomicUsize, Ordering };
+use std::sync::mpsc::channel;
+use std::thread;
+use crate::report::panic_message;
+
+// Number of workers when none is given, one per core
+pub fn default_workers() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+pub fn ordered<T: Sync, R: Send>(jobs: &[T], workers: usize, run: impl Fn(&T) -> R + Sync, mut done: impl FnMut(&T, Result<R, String>)) {
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = channel();
+    thread::scope(|scope| {
+        for _ in 0..workers.clamp(1, jobs.len().max(1)) {
+            let (next, run, sender) = (&next, &run, sender.clone());
+            scope.spawn(move || loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                match jobs.get(i) {
+                    Some(job) => {
+                        let result = panic::catch_unwind(AssertUnwindSafe(|| run(job)))
+                            .map_err(|payload| panic_message(payload.as_ref()));
+                        let _ = sender.send((i, result));
+                    },
+                    None => break
+                }
+            });
+        }
+        drop(sender);
+
+        let mut pending = BTreeMap::new();
+        let mut expected = 0;
+        for (i, result) in receiver {
+            pending.insert(i, result);
+            while let Some(result) = pending.remove(&expected) {
+                done(&jobs[expected], result);
+                expected += 1;
+            }
+        }
+    });
+}
+
+#[test]
+fn test0_pool() {
+    use std::time::Duration;
+
+    let jobs = (0..20).collect::<Vec<u64>>();
+    let mut results = Vec::new();
+    ordered(&jobs, 4, |&job| {
+        thread::sleep(Duration::from_millis(20 - job));
+        job * job
+    }, |_, result| results.push(result.unwrap()));
+    assert_eq!(results, jobs.iter().map(|job| job * job).collect::<Vec<_>>());
+
+    let mut count = 0;
+    ordered(&[] as &[u64], 0, |&job| job, |_, _| count += 1);
+    assert_eq!(count, 0);
+}
+
+#[test]
+fn test1_pool() {
+    // the jobs after the panicking ones still come back, on a single worker too
+    let jobs = (0..10).collect::<Vec<u64>>();
+    for workers in [1, 3] {
+        let mut results = Vec::new();
+        ordered(&jobs, workers, |&job| if job % 4 == 1 { panic!("job {}", job) } else { job }, |&job, result| results.push((job, result)));
+        assert_eq!(results.len(), 10);
+        assert_eq!(results[1], (1, Err("job 1".to_string())));
+        assert_eq!(results[5], (5, Err("job 5".to_string())));
+        assert_eq!(results[9], (9, Err("job 9".to_string())));
+        assert_eq!(results[8], (8, Ok(8)));
+    }
+}
@@ -0,0 +1,145 @@
+//
+// Outcome of running a star, printed as text or as JSON
+//
+// JSON reports are single line objects, with the answer, its type, the solve time and
+// the error, null when the star failed or succeeded respectively:
+//   {"day":1,"star":1,"answer":3372695,"type":"integer","time_us":25.3,"error":null}
+// Stars whose heap usage was measured have the peak, allocations and bytes allocated too:
+//   ..."error":null,"peak_bytes":1024,"allocations":3,"allocated_bytes":2048}
+//
+use std::fmt;
+use std::fmt::Write;
+use std::panic::{ self, AssertUnwindSafe };
+use std::time::{ Duration, Instant };
+use crate::{ Answer, Day, Solution };
+use crate::memory::{ self, Usage };
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub day: usize,
+    pub star: usize,
+    pub title: &'static str,
+    // the error when the input could not be read or the solution panicked
+    pub answer: Result<Answer, String>,
+    pub time: Duration,
+    pub memory: Option<Usage>
+}
+
+impl Report {
+    pub fn to_json(&self) -> String {
+        let (answer, kind, error) = match &self.answer {
+            Ok(answer) => {
+                let value = match answer {
+                    Answer::Integer(value) => value.to_string(),
+                    Answer::Text(text) | Answer::Image(text) => json_string(text)
+                };
+                (value, json_string(answer.kind()), "null".to_string())
+            },
+            Err(error) => ("null".to_string(), "null".to_string(), json_string(error))
+        };
+        let memory = match self.memory {
+            Some(usage) => format!(",\"peak_bytes\":{},\"allocations\":{},\"allocated_bytes\":{}", usage.peak, usage.allocations, usage.bytes),
+            None => String::new()
+        };
+        format!("{{\"day\":{},\"star\":{},\"answer\":{},\"type\":{},\"time_us\":{:.1},\"error\":{}{}}}",
+            self.day, self.star, answer, kind, self.time.as_secs_f64() * 1e6, error, memory)
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "day {:>2}.{} - {}:", self.day, self.star, self.title)?;
+        let usage = self.memory.map_or(String::new(), |usage| format!(" ({})", usage));
+        match &self.answer {
+            Ok(Answer::Image(image)) => write!(f, "{}\n{}", usage, image),
+            Ok(answer) => write!(f, " {}{}", answer, usage),
+            Err(error) => write!(f, " error: {}{}", error, usage)
+        }
+    }
+}
+
+// Solves the star, a panic becomes the error of the report
+pub fn run(day: &Day, star: usize, input: &str) -> Report {
+    let start = Instant::now();
+    let answer = panic::catch_unwind(AssertUnwindSafe(|| day.star(star, input)))
+        .map_err(|payload| panic_message(payload.as_ref()))
+        .and_then(|answer| answer.ok_or_else(|| format!("no star {}", star)));
+    let time = start.elapsed();
+    Report { day: day.day, star, title: title(day, star), answer, time, memory: None }
+}
+
+// Solves the star, measuring its usage of the heap, which needs memory::Counting as the
+// global allocator and counting enabled
+pub fn measured(day: &Day, star: usize, input: &str) -> Report {
+    let (report, usage) = memory::measure(|| run(day, star, input));
+    Report { memory: Some(usage), ..report }
+}
+
+// Report of a star that could not be run
+pub fn failed(day: &Day, star: usize, error: String) -> Report {
+    Report { day: day.day, star, title: title(day, star), answer: Err(error), time: Duration::ZERO, memory: None }
+}
+
+// Stars are numbered from 1, the day has no title for the others
+fn title(day: &Day, star: usize) -> &'static str {
+    day.titles().get(star.wrapping_sub(1)).copied().unwrap_or("unknown star")
+}
+
+pub fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    match (payload.downcast_ref::<&str>(), payload.downcast_ref::<String>()) {
+        (Some(message), _) => message.to_string(),
+        (_, Some(message)) => message.clone(),
+        _ => "panicked".to_string()
+    }
+}
+
+pub fn json_string(text: &str) -> String {
+    let mut json = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(json, "\\u{:04x}", c as u32).unwrap(),
+            c => json.push(c)
+        }
+    }
+    json.push('"');
+    json
+}
+
+#[test]
+fn test0_report() {
+    let day = crate::day(1).unwrap();
+    let report = Report { time: Duration::from_micros(12), ..run(day, 1, "12\n14") };
+    assert_eq!(report.answer, Ok(Answer::Integer(4)));
+    assert_eq!(report.to_string(), "day  1.1 - sum of the fuel requirements: 4");
+    assert_eq!(report.to_json(), r#"{"day":1,"star":1,"answer":4,"type":"integer","time_us":12.0,"error":null}"#);
+
+    let report = Report { answer: Ok(Answer::Image("#.\n\"#".to_string())), ..report };
+    assert_eq!(report.to_string(), "day  1.1 - sum of the fuel requirements:\n#.\n\"#");
+    assert!(report.to_json().contains(r##""answer":"#.\n\"#","type":"image""##));
+
+    let report = Report { memory: Some(Usage { peak: 1024, allocations: 3, bytes: 2048 }), ..report };
+    assert_eq!(report.to_string(), "day  1.1 - sum of the fuel requirements: (peak 1.0KiB, 3 allocations of 2.0KiB)\n#.\n\"#");
+    assert!(report.to_json().ends_with(r#""error":null,"peak_bytes":1024,"allocations":3,"allocated_bytes":2048}"#));
+    let report = Report { answer: Ok(Answer::Integer(4)), ..report };
+    assert_eq!(report.to_string(), "day  1.1 - sum of the fuel requirements: 4 (peak 1.0KiB, 3 allocations of 2.0KiB)");
+    assert!(measured(day, 1, "12").memory.is_some());
+}
+
+#[test]
+fn test1_report() {
+    let day = crate::day(4).unwrap();
+    let report = run(day, 2, "four");
+    assert!(report.answer.is_err());
+    assert!(report.to_json().contains(r#""answer":null,"type":null,"#));
+    assert_eq!(failed(day, 1, "cannot read".to_string()).to_string(), "day  4.1 - count of different passwords: error: cannot read");
+    assert_eq!(json_string("a\u{1}\tb"), r#""a\u0001\tb""#);
+    for star in [0, 3] {
+        assert_eq!(run(day, star, "").to_string(), format!("day  4.{} - unknown star: error: no star {}", star, star));
+    }
+    assert_eq!(failed(day, 0, "cannot read".to_string()).title, "unknown star");
+}
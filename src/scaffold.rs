@@ -0,0 +1,251 @@
+//
+// Generator of new days, in the module of their year
+//
+// The days of YEAR are in the days module, every other year gets a `yearYYYY` module
+// holding its days and its registry, DAYS, which is listed in YEARS. Generating a day
+// creates its module from a template with test stubs, and registers it, along with
+// its year when it's the first day of that year
+//
+use std::fs;
+use std::path::{ Path, PathBuf };
+use crate::YEAR;
+
+const TEMPLATE: &str = "\
+use crate::Answer;
+
+pub fn first_star(contents: &str) -> Answer {
+    let result = impl_first_star(contents);
+
+    Answer::from(result)
+}
+
+pub fn second_star(contents: &str) -> Answer {
+    let result = impl_second_star(contents);
+
+    Answer::from(result)
+}
+
+fn impl_first_star(contents: &str) -> usize {
+    contents.lines().count()
+}
+
+fn impl_second_star(contents: &str) -> usize {
+    contents.lines().count()
+}
+
+#[test]
+fn test0_first_star() {
+    assert_eq!(impl_first_star(\"\"), 0);
+}
+
+#[test]
+fn test0_second_star() {
+    assert_eq!(impl_second_star(\"\"), 0);
+}
+";
+
+pub fn module(year: usize) -> String {
+    format!("year{}", year)
+}
+
+pub fn day_module(day: usize) -> String {
+    format!("day{:02}", day)
+}
+
+// Module of a new year, with its registry
+pub fn year_source(year: usize) -> String {
+    format!("//\n// Solutions of Advent of Code {}\n//\nuse crate::Day;\n\npub static DAYS: &[Day] = &[\n];\n", year)
+}
+
+// Declares the module of the day and adds it to the registry of the year
+pub fn register_day(source: &str, year: usize, day: usize) -> Result<String, String> {
+    let name = day_module(day);
+    let declaration = format!("pub mod {};", name);
+    if source.lines().any(|line| line == declaration) {
+        return Err(format!("{} of {} is already registered", name, year));
+    }
+    let entry = format!("    Day {{
+        year: {},
+        day: {},
+        titles: [
+            \"first star\",
+            \"second star\"
+        ],
+        stars: [{}::first_star, {}::second_star],
+        parse: None
+    }}", year, day, name, name);
+    let source = insert_entry(source, "pub static DAYS: &[Day] = &[", &entry, |other| entry_day(other) > Some(day))?;
+    Ok(declare(&source, &declaration, "pub mod day"))
+}
+
+// Declares the module of the year and adds it to YEARS
+pub fn register_year(source: &str, year: usize) -> Result<String, String> {
+    let name = module(year);
+    let entry = format!("    Year {{ year: {}, days: {}::DAYS }}", year, name);
+    let source = insert_entry(source, "pub static YEARS: &[Year] = &[", &entry, |_| false)?;
+    Ok(declare(&source, &format!("pub mod {};", name), "pub mod "))
+}
+
+// Creates the module of the day in the repository at root, returns the files written
+pub fn generate(root: &Path, year: usize, day: usize) -> Result<Vec<PathBuf>, String> {
+    if year == YEAR {
+        return Err(format!("the days of {} are in src/days and all of them are solved", YEAR));
+    }
+    if !(1..=25).contains(&day) {
+        return Err(format!("day {} is not between 1 and 25", day));
+    }
+    let lib = root.join("src").join("lib.rs");
+    let directory = root.join("src").join(module(year));
+    let year_path = directory.join("mod.rs");
+    let day_path = directory.join(format!("{}.rs", day_module(day)));
+    if !lib.is_file() {
+        return Err(format!("{} not found, run from the root of the repository", lib.display()));
+    }
+    if day_path.exists() {
+        return Err(format!("{} already exists", day_path.display()));
+    }
+
+    let mut written = Vec::new();
+    if !year_path.exists() {
+        let source = register_year(&read(&lib)?, year)?;
+        fs::create_dir_all(&directory).map_err(|e| format!("cannot create {}: {}", directory.display(), e))?;
+        write(&year_path, &year_source(year))?;
+        write(&lib, &source)?;
+        written.push(lib);
+    }
+    let source = register_day(&read(&year_path)?, year, day)?;
+    write(&day_path, TEMPLATE)?;
+    write(&year_path, &source)?;
+    written.push(year_path);
+    written.push(day_path);
+    Ok(written)
+}
+
+// Adds the entry to the array starting at the line, before the first entry going after
+// it, at the end when there is none
+fn insert_entry(source: &str, start: &str, entry: &str, after: impl Fn(&str) -> bool) -> Result<String, String> {
+    let mut lines = source.lines().map(|line| line.to_string()).collect::<Vec<_>>();
+    let start = lines.iter().position(|line| line == start).ok_or(format!("{} not found", start))?;
+    let end = start + lines[start..].iter().position(|line| line == "];").ok_or(format!("end of {} not found", lines[start]))?;
+    // entries start with the lines indented once, but their closing braces
+    let entries = (start + 1..end)
+        .filter(|&i| lines[i].starts_with("    ") && !lines[i].starts_with("     ") && !lines[i].trim_start().starts_with('}'))
+        .collect::<Vec<_>>();
+    let next = entries.iter().enumerate().find(|&(k, &i)| {
+        let last = entries.get(k + 1).copied().unwrap_or(end);
+        after(&lines[i..last].join("\n"))
+    });
+    match next {
+        Some((_, &i)) => lines.insert(i, format!("{},", entry)),
+        None => {
+            if end > start + 1 {
+                lines[end - 1].push(',');
+            }
+            lines.insert(end, entry.to_string());
+        }
+    }
+    Ok(lines.join("\n") + "\n")
+}
+
+// Day of an entry of DAYS
+fn entry_day(entry: &str) -> Option<usize> {
+    entry.lines().find_map(|line| line.trim().strip_prefix("day: ")?.trim_end_matches(',').parse().ok())
+}
+
+// Inserts the declaration among the ones with the same prefix, in order, or before the
+// first item after the header comment when there is none
+fn declare(source: &str, declaration: &str, prefix: &str) -> String {
+    let mut lines = source.lines().map(|line| line.to_string()).collect::<Vec<_>>();
+    let similar = lines.iter().enumerate().filter(|(_, line)| line.starts_with(prefix)).map(|(i, _)| i).collect::<Vec<_>>();
+    let at = match similar.last() {
+        Some(&last) => similar.iter().copied().find(|&i| lines[i].as_str() > declaration).unwrap_or(last + 1),
+        None => {
+            let first = lines.iter().position(|line| !line.starts_with("//") && !line.starts_with("use ") && !line.is_empty()).unwrap_or(lines.len());
+            lines.insert(first, String::new());
+            first
+        }
+    };
+    lines.insert(at, declaration.to_string());
+    lines.join("\n") + "\n"
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|e| format!("cannot read {}: {}", path.display(), e))
+}
+
+fn write(path: &Path, contents: &str) -> Result<(), String> {
+    fs::write(path, contents).map_err(|e| format!("cannot write {}: {}", path.display(), e))
+}
+
+#[test]
+fn test0_scaffold() {
+    let source = register_day(&year_source(2020), 2020, 3).unwrap();
+    let source = register_day(&source, 2020, 1).unwrap();
+    assert_eq!(source, "\
+//
+// Solutions of Advent of Code 2020
+//
+use crate::Day;
+
+pub mod day01;
+pub mod day03;
+
+pub static DAYS: &[Day] = &[
+    Day {
+        year: 2020,
+        day: 1,
+        titles: [
+            \"first star\",
+            \"second star\"
+        ],
+        stars: [day01::first_star, day01::second_star],
+        parse: None
+    },
+    Day {
+        year: 2020,
+        day: 3,
+        titles: [
+            \"first star\",
+            \"second star\"
+        ],
+        stars: [day03::first_star, day03::second_star],
+        parse: None
+    }
+];
+");
+    assert_eq!(register_day(&source, 2020, 1), Err("day01 of 2020 is already registered".to_string()));
+    let source = register_day(&register_day(&source, 2020, 4).unwrap(), 2020, 2).unwrap();
+    let days = source.lines().filter_map(|line| line.trim().strip_prefix("day: ")).collect::<Vec<_>>();
+    assert_eq!(days, ["1,", "2,", "3,", "4,"]);
+    assert!(source.contains("    },\n    Day {\n        year: 2020,\n        day: 4,") && source.ends_with("    }\n];\n"));
+}
+
+#[test]
+fn test1_scaffold() {
+    let lib = "pub mod bench;\npub mod days;\n\npub static YEARS: &[Year] = &[\n    Year { year: YEAR, days: &DAYS }\n];\n";
+    assert_eq!(register_year(lib, 2015).unwrap(), "\
+pub mod bench;
+pub mod days;
+pub mod year2015;
+
+pub static YEARS: &[Year] = &[
+    Year { year: YEAR, days: &DAYS },
+    Year { year: 2015, days: year2015::DAYS }
+];
+");
+
+    // a directory of its own, cleared of what a failed run may have left
+    static RUNS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+    let run = RUNS.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    let root = std::env::temp_dir().join(format!("scaffold_test1_{}_{}", std::process::id(), run));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::write(root.join("src").join("lib.rs"), lib).unwrap();
+    let written = generate(&root, 2015, 7).unwrap();
+    assert_eq!(written, [root.join("src/lib.rs"), root.join("src/year2015/mod.rs"), root.join("src/year2015/day07.rs")]);
+    assert_eq!(generate(&root, 2015, 8).unwrap(), [root.join("src/year2015/mod.rs"), root.join("src/year2015/day08.rs")]);
+    assert!(generate(&root, 2015, 8).unwrap_err().ends_with("day08.rs already exists"));
+    assert!(read(&root.join("src/year2015/mod.rs")).unwrap().contains("pub mod day07;\npub mod day08;\n"));
+    assert!(generate(&root, YEAR, 26).is_err());
+    fs::remove_dir_all(&root).unwrap();
+}
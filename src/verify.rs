@@ -0,0 +1,351 @@
+//
+// Verification of the answers against expected ones, kept in a TOML or JSON file
+//
+// Both formats map days to stars to answers, with keys like `day01` and `star1`:
+//   [day01]                  { "day01": { "star1": 3372695, "star2": "ABC" } }
+//   star1 = 3372695
+//   star2 = "ABC"
+// Only the subset of TOML needed here is understood: tables, comments, integers,
+// basic and literal strings, multi-line ones included. Answers are compared as text,
+// ignoring trailing whitespace, which images are full of
+//
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use crate::{ Day, DAYS };
+use crate::report;
+
+// Expected answers by day and star
+pub type Expected = BTreeMap<(usize, usize), String>;
+
+#[derive(Debug, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for ParseError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    Pass,
+    Mismatch { expected: String, actual: String },
+    // the input could not be read or the solution panicked
+    Fail(String)
+}
+
+// Files ending with .json are JSON, anything else TOML
+pub fn load(path: impl AsRef<Path>) -> Result<Expected, String> {
+    let path = path.as_ref();
+    let text = fs::read_to_string(path).map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+    let expected = match path.extension() {
+        Some(extension) if extension == "json" => parse_json(&text),
+        _ => parse_toml(&text)
+    };
+    expected.map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+pub fn parse_toml(text: &str) -> Result<Expected, ParseError> {
+    let mut parser = Parser::new(text);
+    let mut expected = Expected::new();
+    let mut day = None;
+    loop {
+        parser.skip(true);
+        match parser.peek() {
+            None => break,
+            Some('[') => {
+                parser.next();
+                let name = parser.take_while(|c| c != ']' && c != '\n');
+                parser.expect(']')?;
+                day = Some(parser.key(name.trim(), "day", DAYS.len())?);
+            },
+            Some(_) => {
+                let name = match parser.peek() {
+                    Some(quote @ ('"' | '\'')) => parser.string(quote)?,
+                    _ => parser.take_while(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+                };
+                let star = parser.key(&name, "star", 2)?;
+                let day = day.ok_or_else(|| parser.error(format!("{} outside of a day table", name)))?;
+                parser.skip(false);
+                parser.expect('=')?;
+                parser.skip(false);
+                let answer = match parser.peek() {
+                    Some(quote @ ('"' | '\'')) => parser.string(quote)?,
+                    _ => parser.integer()?
+                };
+                parser.insert(&mut expected, (day, star), answer)?;
+            }
+        }
+        parser.skip(false);
+        match parser.next() {
+            None | Some('\n') => (),
+            Some(c) => return Err(parser.error(format!("unexpected {:?} after the value", c)))
+        }
+    }
+    Ok(expected)
+}
+
+pub fn parse_json(text: &str) -> Result<Expected, ParseError> {
+    let mut parser = Parser::new(text);
+    let mut expected = Expected::new();
+    parser.object(|parser, name| {
+        let day = parser.key(&name, "day", DAYS.len())?;
+        parser.object(|parser, name| {
+            let star = parser.key(&name, "star", 2)?;
+            let answer = match parser.peek() {
+                Some('"') => parser.string('"')?,
+                _ => parser.integer()?
+            };
+            parser.insert(&mut expected, (day, star), answer)
+        })
+    })?;
+    parser.skip(true);
+    match parser.peek() {
+        None => Ok(expected),
+        Some(c) => Err(parser.error(format!("unexpected {:?} after the answers", c)))
+    }
+}
+
+// Runs the star and compares its answer, panics are turned into failures
+pub fn check(day: &Day, star: usize, input: &str, expected: &str) -> Outcome {
+    match report::run(day, star, input).answer {
+        Ok(answer) => {
+            let actual = normalize(&answer.to_string());
+            if actual == normalize(expected) {
+                Outcome::Pass
+            } else {
+                Outcome::Mismatch { expected: normalize(expected), actual }
+            }
+        },
+        Err(message) => Outcome::Fail(message)
+    }
+}
+
+// Lines of both answers, `-` for the expected ones and `+` for the actual ones
+pub fn diff(expected: &str, actual: &str) -> String {
+    let expected = expected.lines().collect::<Vec<_>>();
+    let actual = actual.lines().collect::<Vec<_>>();
+    let mut diff = String::new();
+    for i in 0..expected.len().max(actual.len()) {
+        match (expected.get(i), actual.get(i)) {
+            (Some(e), Some(a)) if e == a => diff += &format!("  {}\n", e),
+            (e, a) => {
+                if let Some(e) = e {
+                    diff += &format!("- {}\n", e);
+                }
+                if let Some(a) = a {
+                    diff += &format!("+ {}\n", a);
+                }
+            }
+        }
+    }
+    diff
+}
+
+fn normalize(answer: &str) -> String {
+    let lines = answer.lines().map(|line| line.trim_end()).collect::<Vec<_>>();
+    lines.join("\n").trim_matches('\n').to_string()
+}
+
+pub(crate) struct Parser {
+    chars: Vec<char>,
+    pos: usize
+}
+
+impl Parser {
+    pub(crate) fn new(text: &str) -> Self {
+        Parser { chars: text.chars().collect(), pos: 0 }
+    }
+
+    pub(crate) fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        self.pos += c.is_some() as usize;
+        c
+    }
+
+    pub(crate) fn error(&self, message: String) -> ParseError {
+        let line = self.chars[..self.pos.min(self.chars.len())].iter().filter(|&&c| c == '\n').count() + 1;
+        ParseError { line, message }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ParseError> {
+        match self.next() {
+            Some(c) if c == expected => Ok(()),
+            Some(c) => Err(self.error(format!("expected {:?}, found {:?}", expected, c))),
+            None => Err(self.error(format!("expected {:?}, found the end", expected)))
+        }
+    }
+
+    pub(crate) fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> String {
+        let start = self.pos;
+        while self.peek().is_some_and(&predicate) {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    // Skips blanks and comments, new lines too when asked
+    fn skip(&mut self, lines: bool) {
+        loop {
+            match self.peek() {
+                Some(' ' | '\t' | '\r') => self.pos += 1,
+                Some('\n') if lines => self.pos += 1,
+                Some('#') => {
+                    self.take_while(|c| c != '\n');
+                },
+                _ => break
+            }
+        }
+    }
+
+    // Number of a day or a star from its key, like day01 or star2
+    fn key(&self, name: &str, prefix: &str, max: usize) -> Result<usize, ParseError> {
+        name.strip_prefix(prefix)
+            .and_then(|n| n.parse::<usize>().ok())
+            .filter(|n| (1..=max).contains(n))
+            .ok_or_else(|| self.error(format!("{} is not a {} between {}1 and {}{}", name, prefix, prefix, prefix, max)))
+    }
+
+    fn insert(&self, expected: &mut Expected, key: (usize, usize), answer: String) -> Result<(), ParseError> {
+        match expected.insert(key, answer) {
+            Some(_) => Err(self.error(format!("answer of day {} star {} given twice", key.0, key.1))),
+            None => Ok(())
+        }
+    }
+
+    // Wide enough for the answers beyond i64, which are given as text
+    fn integer(&mut self) -> Result<String, ParseError> {
+        let text = self.take_while(|c| c.is_ascii_alphanumeric() || "+-_".contains(c));
+        text.replace('_', "")
+            .parse::<i128>()
+            .map(|n| n.to_string())
+            .map_err(|_| self.error(format!("{:?} is neither an integer nor a string", text)))
+    }
+
+    // Basic strings with escapes for `"`, literal ones for `'`, tripled for multi-line ones
+    pub(crate) fn string(&mut self, quote: char) -> Result<String, ParseError> {
+        let multiline = self.chars[self.pos..].starts_with(&[quote; 3]);
+        self.pos += if multiline { 3 } else { 1 };
+        if multiline && self.peek() == Some('\n') {
+            self.pos += 1;
+        }
+        let mut string = String::new();
+        loop {
+            match self.next() {
+                None => return Err(self.error("unterminated string".to_string())),
+                Some('\n') if !multiline => return Err(self.error("new line in a string".to_string())),
+                Some(c) if c == quote && !multiline => return Ok(string),
+                Some(c) if c == quote && self.chars[self.pos..].starts_with(&[quote; 2]) => {
+                    self.pos += 2;
+                    return Ok(string);
+                },
+                Some('\\') if quote == '"' => string.push(self.escape()?),
+                Some(c) => string.push(c)
+            }
+        }
+    }
+
+    fn escape(&mut self) -> Result<char, ParseError> {
+        match self.next() {
+            Some('n') => Ok('\n'),
+            Some('t') => Ok('\t'),
+            Some('r') => Ok('\r'),
+            Some(c @ ('"' | '\\' | '/')) => Ok(c),
+            Some('u') => {
+                let code = (0..4).filter_map(|_| self.next()).collect::<String>();
+                u32::from_str_radix(&code, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| self.error(format!("wrong escape \\u{}", code)))
+            },
+            c => Err(self.error(format!("wrong escape {:?}", c)))
+        }
+    }
+
+    // JSON object, the value of each member is parsed by the closure
+    pub(crate) fn object(&mut self, mut member: impl FnMut(&mut Self, String) -> Result<(), ParseError>) -> Result<(), ParseError> {
+        self.skip(true);
+        self.expect('{')?;
+        self.skip(true);
+        if self.peek() == Some('}') {
+            self.pos += 1;
+            return Ok(());
+        }
+        loop {
+            self.skip(true);
+            if self.peek() != Some('"') {
+                return Err(self.error("expected a key".to_string()));
+            }
+            let name = self.string('"')?;
+            self.skip(true);
+            self.expect(':')?;
+            self.skip(true);
+            member(self, name)?;
+            self.skip(true);
+            match self.next() {
+                Some(',') => (),
+                Some('}') => return Ok(()),
+                _ => return Err(self.error("expected ',' or '}'".to_string()))
+            }
+        }
+    }
+}
+
+#[test]
+fn test0_toml() {
+    let expected = parse_toml("\
+        # answers\n\
+        [day01]\n\
+        star1 = 3_372_695 # fuel\n\
+        'star2' = \"a\\tb\"\n\
+        \n\
+        [ day8 ]\n\
+        star2 = '''\n\
+        * *  \n\
+        \x20* '''\n").unwrap();
+    assert_eq!(expected.into_iter().collect::<Vec<_>>(), [
+        ((1, 1), "3372695".to_string()),
+        ((1, 2), "a\tb".to_string()),
+        ((8, 2), "* *  \n * ".to_string())
+    ]);
+    assert_eq!(parse_toml("star1 = 1"), Err(ParseError { line: 1, message: "star1 outside of a day table".to_string() }));
+    assert_eq!(parse_toml("[day1]\nstar3 = 1"), Err(ParseError { line: 2, message: "star3 is not a star between star1 and star2".to_string() }));
+    assert_eq!(parse_toml("[day26]"), Err(ParseError { line: 1, message: "day26 is not a day between day1 and day25".to_string() }));
+    assert_eq!(parse_toml("[day1]\nstar1 = 1 2").map_err(|e| e.line), Err(2));
+    assert_eq!(parse_toml("[day1]\nstar1 = \"1\nstar2 = 2").map_err(|e| e.message), Err("new line in a string".to_string()));
+}
+
+#[test]
+fn test1_json() {
+    let expected = parse_json(r#"{
+        "day01": { "star1": 3372695, "star2": "ABC" },
+        "day25": {}
+    }"#).unwrap();
+    assert_eq!(expected.into_iter().collect::<Vec<_>>(), [((1, 1), "3372695".to_string()), ((1, 2), "ABC".to_string())]);
+    let expected = parse_json(r#"{ "day1": { "star1": 18446744073709551615 } }"#).unwrap();
+    assert_eq!(expected.get(&(1, 1)).map(|answer| answer.as_str()), Some(u64::MAX.to_string().as_str()));
+    assert_eq!(parse_json(r#"{ "day1": { "star1": 1, "star1": 2 } }"#).map_err(|e| e.message), Err("answer of day 1 star 1 given twice".to_string()));
+    assert_eq!(parse_json(r#"{ "day1": { "star1": 1 } ]"#).map_err(|e| e.message), Err("expected ',' or '}'".to_string()));
+}
+
+#[test]
+fn test2_check() {
+    let day = crate::day(1).unwrap();
+    assert_eq!(check(day, 1, "12\n14", "4"), Outcome::Pass);
+    assert_eq!(check(day, 1, "12\n14", "5"), Outcome::Mismatch { expected: "5".to_string(), actual: "4".to_string() });
+    assert!(matches!(check(day, 1, "twelve", "4"), Outcome::Fail(_)));
+    assert_eq!(normalize("\n* *  \n *\n\n"), "* *\n *");
+    assert_eq!(diff("a\nb", "a\nc\nd"), "  a\n- b\n+ c\n+ d\n");
+}
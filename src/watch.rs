@@ -0,0 +1,189 @@
+//
+// Watch mode, solving a day again whenever its module or its input change
+//
+// Changes are found by polling the modification times of the files. The runner can't
+// reload its own code, so days are solved by a fresh build of it, through cargo, whose
+// JSON reports are compared with the ones of the previous run. The tests of the day
+// are run after its stars
+//
+use std::fs;
+use std::path::{ Path, PathBuf };
+use std::process::Command;
+use std::time::{ Duration, SystemTime };
+use crate::bench::duration;
+use crate::verify::{ self, ParseError, Parser };
+use crate::{ scaffold, Day, YEAR };
+
+// Time between two looks at the files
+pub const POLL: Duration = Duration::from_millis(500);
+
+// Answer of a star, read from its JSON report
+#[derive(Debug, Clone, PartialEq)]
+pub struct Star {
+    pub day: usize,
+    pub star: usize,
+    pub answer: Result<String, String>,
+    pub time: Duration
+}
+
+pub struct Watcher {
+    files: Vec<(PathBuf, Option<SystemTime>)>
+}
+
+impl Watcher {
+    pub fn new(paths: impl IntoIterator<Item = PathBuf>) -> Self {
+        Watcher { files: paths.into_iter().map(|path| { let time = modified(&path); (path, time) }).collect() }
+    }
+
+    // Files modified, created or removed since the last call
+    pub fn changed(&mut self) -> Vec<PathBuf> {
+        let mut changed = Vec::new();
+        for (path, time) in self.files.iter_mut() {
+            let now = modified(path);
+            if now != *time {
+                *time = now;
+                changed.push(path.clone());
+            }
+        }
+        changed
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+fn module(day: &Day) -> String {
+    if day.year == YEAR { "days".to_string() } else { scaffold::module(day.year) }
+}
+
+// Module of the day, relative to the root of the repository
+pub fn source_path(day: &Day) -> PathBuf {
+    Path::new("src").join(module(day)).join(format!("{}.rs", scaffold::day_module(day.day)))
+}
+
+// Filter of cargo test selecting the tests of the day
+pub fn test_filter(day: &Day) -> String {
+    format!("{}::{}::", module(day), scaffold::day_module(day.day))
+}
+
+// Builds the runner and solves the stars of the day, the errors of cargo when the build fails
+pub fn solve(day: &Day) -> Result<Vec<Star>, String> {
+    let output = Command::new("cargo")
+        .args(["run", "--release", "--quiet", "--", "run", &day.day.to_string(), "--format", "json", "--year", &day.year.to_string()])
+        .output()
+        .map_err(|e| format!("cannot run cargo: {}", e))?;
+    let stars = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| parse_report(line).map_err(|e| format!("report {:?}: {}", line, e)))
+        .collect::<Result<Vec<_>, _>>()?;
+    if stars.is_empty() && !output.status.success() {
+        return Err(format!("build failed\n{}", String::from_utf8_lossy(&output.stderr).trim_end()));
+    }
+    Ok(stars)
+}
+
+// Runs the tests of the day, the summary of cargo when they pass, its output otherwise
+pub fn test(day: &Day) -> Result<String, String> {
+    let output = Command::new("cargo")
+        .args(["test", "--release", "--quiet", "--lib", &test_filter(day)])
+        .output()
+        .map_err(|e| format!("cannot run cargo: {}", e))?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    match stdout.lines().find(|line| line.starts_with("test result:")) {
+        Some(summary) if output.status.success() => Ok(summary.to_string()),
+        _ if stdout.trim().is_empty() => Err(String::from_utf8_lossy(&output.stderr).trim_end().to_string()),
+        _ => Err(stdout.trim_end().to_string())
+    }
+}
+
+// Star of a report printed by `run --format json`
+pub fn parse_report(line: &str) -> Result<Star, ParseError> {
+    let mut parser = Parser::new(line);
+    let (mut day, mut star, mut answer, mut error, mut time) = (None, None, None, None, Duration::ZERO);
+    parser.object(|parser, name| {
+        let value = match parser.peek() {
+            Some('"') => Some(parser.string('"')?),
+            _ => Some(parser.take_while(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))).filter(|value| value != "null")
+        };
+        match name.as_str() {
+            "day" => day = value.and_then(|value| value.parse().ok()),
+            "star" => star = value.and_then(|value| value.parse().ok()),
+            "answer" => answer = value,
+            "error" => error = value,
+            "time_us" => time = value.and_then(|value| value.parse::<f64>().ok()).map_or(Duration::ZERO, |us| Duration::from_secs_f64(us / 1e6)),
+            _ => ()
+        }
+        Ok(())
+    })?;
+    let (day, star) = day.zip(star).ok_or_else(|| parser.error("day or star missing".to_string()))?;
+    let answer = match (answer, error) {
+        (_, Some(error)) => Err(error),
+        (Some(answer), None) => Ok(answer),
+        (None, None) => Err("no answer".to_string())
+    };
+    Ok(Star { day, star, answer, time })
+}
+
+// Answer and time of the star, along with the changes since the previous run
+pub fn compare(previous: Option<&Star>, current: &Star) -> String {
+    let label = format!("day {:>2}.{}:", current.day, current.star);
+    let answer = match &current.answer {
+        Ok(answer) => answer,
+        Err(error) => return format!("{} error: {}", label, error)
+    };
+    let mut time = format!("in {}", duration(current.time));
+    if let Some(previous) = previous.filter(|previous| previous.answer.is_ok()) {
+        let change = current.time.as_secs_f64() / previous.time.as_secs_f64().max(1e-9) - 1.0;
+        time += &format!(", was {} ({:+.1}%)", duration(previous.time), change * 100.0);
+    }
+    match previous.map(|previous| &previous.answer) {
+        Some(Ok(before)) if before != answer && (before.contains('\n') || answer.contains('\n')) =>
+            format!("{} {}, answer changed\n{}", label, time, verify::diff(before, answer).trim_end()),
+        Some(Ok(before)) if before != answer => format!("{} {} {}, answer was {}", label, answer, time, before),
+        _ if answer.contains('\n') => format!("{} {}\n{}", label, time, answer),
+        _ => format!("{} {} {}", label, answer, time)
+    }
+}
+
+#[test]
+fn test0_watch() {
+    use crate::report::Report;
+
+    let day = crate::day(1).unwrap();
+    let report = Report { time: Duration::from_micros(1500), ..crate::report::run(day, 1, "12\n14") };
+    let star = parse_report(&report.to_json()).unwrap();
+    assert_eq!(star, Star { day: 1, star: 1, answer: Ok("4".to_string()), time: Duration::from_micros(1500) });
+    assert_eq!(compare(None, &star), "day  1.1: 4 in 1.50ms");
+
+    let faster = Star { time: Duration::from_micros(750), ..star.clone() };
+    assert_eq!(compare(Some(&star), &faster), "day  1.1: 4 in 750.0µs, was 1.50ms (-50.0%)");
+    let changed = Star { answer: Ok("5".to_string()), ..star.clone() };
+    assert_eq!(compare(Some(&star), &changed), "day  1.1: 5 in 1.50ms, was 1.50ms (+0.0%), answer was 4");
+    let failed = parse_report(r#"{"day":4,"star":2,"answer":null,"type":null,"time_us":0.0,"error":"bad \"input\""}"#).unwrap();
+    let image = Star { answer: Ok("#.\n.#".to_string()), ..star.clone() };
+    assert_eq!(compare(Some(&Star { answer: Ok("#.\n##".to_string()), ..star.clone() }), &image), "day  1.1: in 1.50ms, was 1.50ms (+0.0%), answer changed\n  #.\n- ##\n+ .#");
+    assert_eq!(compare(Some(&failed), &image), "day  1.1: in 1.50ms\n#.\n.#");
+    assert_eq!(compare(Some(&star), &failed), "day  4.2: error: bad \"input\"");
+    assert!(parse_report(r#"{"star":2}"#).is_err());
+}
+
+#[test]
+fn test1_watch() {
+    let day = crate::day(5).unwrap();
+    assert_eq!(source_path(day), Path::new("src/days/day05.rs"));
+    assert_eq!(test_filter(day), "days::day05::");
+
+    // a file of its own, cleared of what a failed run may have left
+    static RUNS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+    let run = RUNS.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    let path = std::env::temp_dir().join(format!("watch_test1_{}_{}.txt", std::process::id(), run));
+    let _ = fs::remove_file(&path);
+    let mut watcher = Watcher::new([path.clone()]);
+    assert!(watcher.changed().is_empty());
+    fs::write(&path, "1").unwrap();
+    assert_eq!(watcher.changed(), vec![path.clone()]);
+    assert!(watcher.changed().is_empty());
+    fs::remove_file(&path).unwrap();
+    assert_eq!(watcher.changed(), [path]);
+}